@@ -0,0 +1,94 @@
+/*!
+ * Batch Chat Completions
+ *
+ * Runs many chat completions concurrently at batch priority, sending each
+ * distinct request only once and fanning the shared result out to every
+ * duplicate.
+ */
+
+use std::collections::HashMap;
+use futures::StreamExt;
+use tracing::info;
+
+use crate::client::LmoClient;
+use crate::error::ClientResult;
+use crate::queue::RequestPriority;
+use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse};
+
+impl LmoClient {
+    /// Run chat completions with at most `max_parallel` in flight, returning results in input order
+    ///
+    /// Identical requests are sent once and share the response, so sampling
+    /// the same prompt several times needs distinct requests (e.g. a different `seed`).
+    pub async fn chat_completions_concurrent(
+        &self,
+        requests: Vec<ChatCompletionRequest>,
+        max_parallel: usize,
+    ) -> Vec<ClientResult<ChatCompletionResponse>> {
+        let total = requests.len();
+        let (unique, slots) = dedup_requests(requests);
+        info!(
+            "Running {} chat completions ({} unique, max {} parallel)",
+            total,
+            unique.len(),
+            max_parallel
+        );
+
+        let results: Vec<_> = futures::stream::iter(unique)
+            .map(|request| self.chat_completion_with_priority(request, RequestPriority::Batch))
+            .buffered(max_parallel.max(1))
+            .collect()
+            .await;
+
+        slots
+            .into_iter()
+            .map(|slot| match &results[slot] {
+                Ok(response) => Ok(response.clone()),
+                Err(e) => Err(e.duplicate()),
+            })
+            .collect()
+    }
+}
+
+/// Split requests into distinct ones and, per input, the index of its distinct request
+fn dedup_requests(requests: Vec<ChatCompletionRequest>) -> (Vec<ChatCompletionRequest>, Vec<usize>) {
+    let mut unique = Vec::new();
+    let mut slots = Vec::with_capacity(requests.len());
+    let mut seen: HashMap<String, usize> = HashMap::new();
+
+    for request in requests {
+        // Requests that fail to serialize are never treated as duplicates
+        let key = serde_json::to_string(&request).ok();
+        if let Some(&slot) = key.as_ref().and_then(|key| seen.get(key)) {
+            slots.push(slot);
+            continue;
+        }
+
+        if let Some(key) = key {
+            seen.insert(key, unique.len());
+        }
+        slots.push(unique.len());
+        unique.push(request);
+    }
+
+    (unique, slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChatRequestBuilder;
+
+    fn request(prompt: &str) -> ChatCompletionRequest {
+        ChatRequestBuilder::new().model("m").message("user", prompt).build()
+    }
+
+    #[test]
+    fn test_dedup_requests() {
+        let (unique, slots) = dedup_requests(vec![request("a"), request("b"), request("a"), request("b"), request("c")]);
+
+        assert_eq!(unique.len(), 3);
+        assert_eq!(slots, vec![0, 1, 0, 1, 2]);
+        assert_eq!(unique[2].messages[0].content, "c");
+    }
+}
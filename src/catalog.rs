@@ -0,0 +1,238 @@
+/*!
+ * Cached Model Catalog
+ * 
+ * On-disk cache of the model list with a configurable TTL, so repeated
+ * listings are instant and remain available when the server is offline.
+ * Each snapshot records which server it came from and is only served back
+ * to clients of that server.
+ */
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::client::LmoClient;
+use crate::error::ClientResult;
+use crate::models::ModelListResponse;
+
+/// Model list snapshot stored in the catalog cache
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedModelList {
+    /// When the list was fetched from the server
+    pub fetched_at: DateTime<Utc>,
+    /// Hash of the URL of the server the list was fetched from
+    #[serde(default)]
+    pub server: String,
+    /// Whether this snapshot was served from disk rather than the server
+    #[serde(skip)]
+    pub from_cache: bool,
+    /// The cached model list
+    pub response: ModelListResponse,
+}
+
+impl CachedModelList {
+    /// Time elapsed since the list was fetched
+    pub fn age(&self) -> chrono::Duration {
+        Utc::now().signed_duration_since(self.fetched_at)
+    }
+}
+
+/// On-disk model catalog cache
+#[derive(Debug, Clone)]
+pub struct ModelCatalogCache {
+    path: PathBuf,
+    ttl: Duration,
+}
+
+impl ModelCatalogCache {
+    /// Create a cache stored at the given file path
+    pub fn new<P: Into<PathBuf>>(path: P, ttl: Duration) -> Self {
+        Self {
+            path: path.into(),
+            ttl,
+        }
+    }
+
+    /// Get the cache file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the cache TTL
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Load the list cached for the given server regardless of its age
+    ///
+    /// A cache file that cannot be parsed or was written for another server
+    /// is treated as missing, so the next fetch overwrites it.
+    pub fn load(&self, server_url: &str) -> ClientResult<Option<CachedModelList>> {
+        if !self.path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(&self.path)?;
+        let mut cached: CachedModelList = match serde_json::from_str(&contents) {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("Ignoring unreadable model catalog cache at {}: {}", self.path.display(), e);
+                return Ok(None);
+            }
+        };
+        if cached.server != server_key(server_url) {
+            debug!("Model catalog cache at {} belongs to another server", self.path.display());
+            return Ok(None);
+        }
+        cached.from_cache = true;
+
+        Ok(Some(cached))
+    }
+
+    /// Load the list cached for the given server only if it is younger than the TTL
+    pub fn load_fresh(&self, server_url: &str) -> ClientResult<Option<CachedModelList>> {
+        Ok(self.load(server_url)?.filter(|cached| self.is_fresh(cached)))
+    }
+
+    /// Check whether a cached list is still within the TTL
+    pub fn is_fresh(&self, cached: &CachedModelList) -> bool {
+        cached
+            .age()
+            .to_std()
+            .map(|age| age < self.ttl)
+            .unwrap_or(true) // Negative age means clock skew, treat as fresh
+    }
+
+    /// Store a list freshly fetched from the given server
+    pub fn store(&self, server_url: &str, response: &ModelListResponse) -> ClientResult<CachedModelList> {
+        let cached = CachedModelList {
+            fetched_at: Utc::now(),
+            server: server_key(server_url),
+            from_cache: false,
+            response: response.clone(),
+        };
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // Write a sibling file and rename it over the cache, so readers never see a partial file
+        let mut temp_name = self.path.file_name().unwrap_or_default().to_os_string();
+        temp_name.push(format!(".{}.tmp", std::process::id()));
+        let temp_path = self.path.with_file_name(temp_name);
+        std::fs::write(&temp_path, serde_json::to_string(&cached)?)?;
+        if let Err(e) = std::fs::rename(&temp_path, &self.path) {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        debug!("Stored model catalog cache at {}", self.path.display());
+
+        Ok(cached)
+    }
+
+    /// Remove the cache file if present
+    pub fn clear(&self) -> ClientResult<()> {
+        if self.path.exists() {
+            std::fs::remove_file(&self.path)?;
+            debug!("Cleared model catalog cache at {}", self.path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Stable key for the server a list was fetched from
+fn server_key(server_url: &str) -> String {
+    // FNV-1a, since the std hasher may change between Rust releases
+    let hash = server_url
+        .trim_end_matches('/')
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+impl LmoClient {
+    /// List available models through the catalog cache
+    ///
+    /// Returns the cached list while it is fresh. Otherwise fetches from the
+    /// server and updates the cache, falling back to a stale cached list if
+    /// the server cannot be reached.
+    pub async fn list_models_cached(&self, cache: &ModelCatalogCache) -> ClientResult<CachedModelList> {
+        let server_url = &self.config().server_url;
+        if let Some(cached) = cache.load_fresh(server_url)? {
+            debug!("Using cached model catalog ({}s old)", cached.age().num_seconds());
+            return Ok(cached);
+        }
+
+        match self.list_models().await {
+            Ok(response) => cache.store(server_url, &response),
+            Err(e) if e.is_retryable() => match cache.load(server_url)? {
+                Some(stale) => {
+                    warn!("Server unavailable, using stale model catalog: {}", e);
+                    Ok(stale)
+                }
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Clear the catalog cache and refetch, bypassing the server hub cache
+    pub async fn refresh_models_cached(&self, cache: &ModelCatalogCache) -> ClientResult<CachedModelList> {
+        cache.clear()?;
+        let response = self.list_models_refresh().await?;
+        cache.store(&self.config().server_url, &response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_list() -> ModelListResponse {
+        ModelListResponse {
+            models: vec![],
+            total: Some(0),
+            has_more: false,
+        }
+    }
+
+    #[test]
+    fn test_cache_round_trip() {
+        let path = std::env::temp_dir().join(format!("lmoclient-catalog-{}.json", std::process::id()));
+        let cache = ModelCatalogCache::new(&path, Duration::from_secs(300));
+
+        let server = "http://localhost:3000";
+        assert!(cache.load(server).unwrap().is_none());
+
+        let stored = cache.store(server, &empty_list()).unwrap();
+        assert!(!stored.from_cache);
+
+        let loaded = cache.load_fresh("http://localhost:3000/").unwrap().expect("cache should be fresh");
+        assert!(loaded.from_cache);
+        assert_eq!(loaded.response.total, Some(0));
+        assert!(cache.load("http://gpu-box:3000").unwrap().is_none());
+
+        std::fs::write(&path, "{\"fetched_at\": \"2024-").unwrap();
+        assert!(cache.load(server).unwrap().is_none());
+
+        cache.clear().unwrap();
+        assert!(cache.load(server).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let cache = ModelCatalogCache::new("unused.json", Duration::from_secs(60));
+        let mut cached = CachedModelList {
+            fetched_at: Utc::now(),
+            server: server_key("http://localhost:3000"),
+            from_cache: true,
+            response: empty_list(),
+        };
+        assert!(cache.is_fresh(&cached));
+
+        cached.fetched_at = Utc::now() - chrono::Duration::seconds(120);
+        assert!(!cache.is_fresh(&cached));
+    }
+}
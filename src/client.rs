@@ -4,26 +4,62 @@
  * HTTP client for communicating with the LMOxide server.
  */
 
-use reqwest::{Client, Response};
+use futures::Stream;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::{Client, RequestBuilder, Response};
+use std::sync::{Arc, Mutex};
+use tokio_stream::StreamExt;
 use tracing::{debug, info, warn};
 
+use crate::coalesce::Coalescer;
+use crate::catalog::ModelCatalogCache;
 use crate::config::{ClientConfig, Endpoints};
 use crate::error::{ClientError, ClientResult};
+use crate::guardrail::{Guardrail, GuardrailPolicy, Guardrails};
+use crate::json_stream::JsonArrayParser;
 use crate::models::{
     ChatRequestBuilder, DownloadModelRequest, DownloadModelResponse, HealthInfo, 
     LoadModelRequest, LoadModelResponse, ModelListResponse, ModelStatusInfo, 
-    UnloadModelRequest, UnloadModelResponse, LocalModelsResponse,
+    PipelineTag, UnloadModelRequest, UnloadModelResponse, LocalModelsResponse,
 };
+use crate::meta::{ResponseMeta, WithMeta};
+use crate::queue::{PriorityLimiter, PriorityPermit, RequestPriority};
+use crate::rate_limit::RateLimitInfo;
+use crate::semantic_cache::SemanticCache;
 use crate::streaming::ChatCompletionStream;
 
 // Re-export server types
-use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse, ModelInfo};
+use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage, ModelInfo};
+
+/// Header carrying the configured client tags
+const CLIENT_TAGS_HEADER: &str = "x-client-tags";
+
+/// Hook applied to every outgoing request, streaming ones included
+pub type RequestHook = Arc<dyn Fn(RequestBuilder) -> RequestBuilder + Send + Sync>;
+
+/// Registered request hooks, applied in order
+#[derive(Clone, Default)]
+struct RequestHooks(Vec<RequestHook>);
+
+impl std::fmt::Debug for RequestHooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RequestHooks({})", self.0.len())
+    }
+}
 
 /// Main HTTP client for LMOxide server
 #[derive(Debug, Clone)]
 pub struct LmoClient {
     client: Client,
     config: ClientConfig,
+    rate_limit: Arc<Mutex<Option<RateLimitInfo>>>,
+    in_flight: Option<Arc<PriorityLimiter>>,
+    health_requests: Arc<Coalescer<WithMeta<HealthInfo>>>,
+    model_list_requests: Arc<Coalescer<WithMeta<ModelListResponse>>>,
+    request_hooks: RequestHooks,
+    catalog: Option<ModelCatalogCache>,
+    guardrails: Guardrails,
+    semantic_cache: Option<Arc<SemanticCache>>,
 }
 
 impl LmoClient {
@@ -43,28 +79,103 @@ impl LmoClient {
         // Validate configuration
         config.validate()?;
 
-        // Build HTTP client
-        let mut client_builder = Client::builder()
-            .timeout(config.timeout)
-            .user_agent(&config.user_agent);
+        // Build HTTP client; overall timeouts are set per request so long-lived
+        // streams are only cut off when they go quiet
+        let client = Client::builder()
+            .read_timeout(config.stream_timeout)
+            .user_agent(config.effective_user_agent())
+            .default_headers(Self::default_headers(&config)?)
+            .build()
+            .map_err(|e| ClientError::ConfigError(format!("Failed to create HTTP client: {}", e)))?;
+
+        // Limit concurrent inference calls if configured
+        let in_flight = config.max_in_flight.map(|limit| Arc::new(PriorityLimiter::new(limit)));
+
+        Ok(Self {
+            client,
+            config,
+            rate_limit: Arc::new(Mutex::new(None)),
+            in_flight,
+            health_requests: Arc::new(Coalescer::new()),
+            model_list_requests: Arc::new(Coalescer::new()),
+            request_hooks: RequestHooks::default(),
+            catalog: None,
+            guardrails: Guardrails::default(),
+            semantic_cache: None,
+        })
+    }
+
+    /// Build the headers sent with every request: custom headers, tags and authentication
+    fn default_headers(config: &ClientConfig) -> ClientResult<HeaderMap> {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in &config.default_headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| ClientError::ConfigError(format!("Invalid header name '{}': {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| ClientError::ConfigError(format!("Invalid value for header '{}': {}", name, e)))?;
+            headers.insert(name, value);
+        }
+
+        if let Some(tags) = config.tags_header_value() {
+            headers.insert(
+                HeaderName::from_static(CLIENT_TAGS_HEADER),
+                HeaderValue::from_str(&tags)
+                    .map_err(|e| ClientError::ConfigError(format!("Invalid client tags: {}", e)))?,
+            );
+        }
 
         // Add authentication if provided
         if let Some(ref api_key) = config.api_key {
-            let mut headers = reqwest::header::HeaderMap::new();
             let auth_header = format!("Bearer {}", api_key);
             headers.insert(
-                reqwest::header::AUTHORIZATION,
-                reqwest::header::HeaderValue::from_str(&auth_header)
+                AUTHORIZATION,
+                HeaderValue::from_str(&auth_header)
                     .map_err(|e| ClientError::ConfigError(format!("Invalid API key: {}", e)))?,
             );
-            client_builder = client_builder.default_headers(headers);
         }
 
-        let client = client_builder
-            .build()
-            .map_err(|e| ClientError::ConfigError(format!("Failed to create HTTP client: {}", e)))?;
+        Ok(headers)
+    }
+
+    /// Add a hook that can modify every request before it is sent (extra headers, signing, ...)
+    pub fn with_request_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(RequestBuilder) -> RequestBuilder + Send + Sync + 'static,
+    {
+        self.request_hooks.0.push(Arc::new(hook));
+        self
+    }
+
+    /// Run every chat completion, streamed or not, through a guardrail before returning it
+    ///
+    /// Streamed completions are checked chunk by chunk together with a short
+    /// held-back tail, so a violation split across chunks can still be redacted.
+    pub fn with_guardrail<G: Guardrail + 'static>(mut self, guardrail: G, policy: GuardrailPolicy) -> Self {
+        self.guardrails.push(Arc::new(guardrail), policy);
+        self
+    }
+
+    /// Use a catalog cache for model ID suggestions instead of fetching the model list
+    pub fn with_catalog_cache(mut self, cache: ModelCatalogCache) -> Self {
+        self.catalog = Some(cache);
+        self
+    }
+
+    /// Get the catalog cache, if configured
+    pub fn catalog_cache(&self) -> Option<&ModelCatalogCache> {
+        self.catalog.as_ref()
+    }
 
-        Ok(Self { client, config })
+    /// Answer `chat_completion_cached` calls from a semantic cache
+    pub fn with_semantic_cache(mut self, cache: SemanticCache) -> Self {
+        self.semantic_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Get the semantic cache, if configured
+    pub fn semantic_cache(&self) -> Option<&SemanticCache> {
+        self.semantic_cache.as_deref()
     }
 
     /// Get client configuration
@@ -72,25 +183,156 @@ impl LmoClient {
         &self.config
     }
 
+    /// Get the rate-limit state reported on the most recent response
+    pub fn last_rate_limit(&self) -> Option<RateLimitInfo> {
+        self.rate_limit.lock().ok().and_then(|guard| guard.clone())
+    }
+
+    /// Wait for a free inference slot when `max_in_flight` is configured
+    async fn acquire_inference_permit(&self, priority: RequestPriority) -> ClientResult<Option<PriorityPermit>> {
+        match &self.in_flight {
+            Some(limiter) => Ok(Some(limiter.acquire(priority).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Number of inference calls waiting for a free slot
+    pub fn queued_requests(&self) -> usize {
+        self.in_flight.as_ref().map(|limiter| limiter.queued()).unwrap_or(0)
+    }
+
+    /// Record rate-limit headers from a response, if any were sent
+    fn record_rate_limit(&self, response: &Response) -> Option<RateLimitInfo> {
+        let info = RateLimitInfo::from_headers(response.headers())?;
+        if let Ok(mut guard) = self.rate_limit.lock() {
+            *guard = Some(info.clone());
+        }
+        Some(info)
+    }
+
     /// Check server health
     pub async fn health(&self) -> ClientResult<HealthInfo> {
+        self.health_with_meta().await.map(WithMeta::into_body)
+    }
+
+    /// Check server health, with response metadata
+    ///
+    /// Concurrent calls share a single in-flight request.
+    pub async fn health_with_meta(&self) -> ClientResult<WithMeta<HealthInfo>> {
+        let client = self.clone();
+        self.health_requests.run(|| async move { client.fetch_health().await }).await
+    }
+
+    /// Fetch server health from the server
+    async fn fetch_health(&self) -> ClientResult<WithMeta<HealthInfo>> {
         debug!("Checking server health");
         
         let url = self.config.api_url(Endpoints::HEALTH)?;
         let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let health: HealthInfo = response.json().await?;
         info!("Server health check completed: {}", health.status);
         
-        Ok(health)
+        Ok(WithMeta { body: health, meta })
     }
 
     /// List available models
     pub async fn list_models(&self) -> ClientResult<ModelListResponse> {
+        self.list_models_with_meta().await.map(WithMeta::into_body)
+    }
+
+    /// List available models, with response metadata
+    ///
+    /// Concurrent calls share a single in-flight request.
+    pub async fn list_models_with_meta(&self) -> ClientResult<WithMeta<ModelListResponse>> {
         debug!("Listing available models");
         
         let url = self.config.api_url(Endpoints::MODELS_LIST)?;
+        let client = self.clone();
+        self.model_list_requests.run(|| async move { client.fetch_model_list(url).await }).await
+    }
+
+    /// List available models, asking the server to bypass its hub cache
+    pub async fn list_models_refresh(&self) -> ClientResult<ModelListResponse> {
+        self.list_models_refresh_with_meta().await.map(WithMeta::into_body)
+    }
+
+    /// List available models bypassing the server's hub cache, with response metadata
+    pub async fn list_models_refresh_with_meta(&self) -> ClientResult<WithMeta<ModelListResponse>> {
+        debug!("Listing available models (refresh)");
+        
+        let url = self.config.api_url(Endpoints::MODELS_LIST)?;
+        self.fetch_model_list(format!("{}?refresh=true", url)).await
+    }
+
+    /// List available models whose hub pipeline tag matches `tag`
+    ///
+    /// Models listed without a pipeline tag are left out.
+    pub async fn list_models_by_pipeline(&self, tag: &PipelineTag) -> ClientResult<Vec<ModelInfo>> {
+        debug!("Listing available models with pipeline tag {}", tag);
+        
+        let url = self.config.api_url(Endpoints::MODELS_LIST)?;
+        let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        
+        // Read the tag from the raw entries, which carry more than ModelInfo
+        let entries: Vec<serde_json::Value> = response.json().await?;
+        let models = entries
+            .into_iter()
+            .filter(|entry| PipelineTag::of_model_entry(entry).as_ref() == Some(tag))
+            .map(serde_json::from_value)
+            .collect::<Result<Vec<ModelInfo>, _>>()?;
+        info!("Listed {} {} models", models.len(), tag);
+        
+        Ok(models)
+    }
+
+    /// Stream available models as they are parsed, without buffering the whole list
+    pub async fn list_models_stream(&self) -> ClientResult<impl Stream<Item = ClientResult<ModelInfo>>> {
+        debug!("Streaming available models");
+        
+        let url = self.config.api_url(Endpoints::MODELS_LIST)?;
+        let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        
+        Ok(async_stream::stream! {
+            let mut parser = JsonArrayParser::new();
+            let mut bytes_stream = response.bytes_stream();
+            let mut count = 0usize;
+            
+            while let Some(chunk) = bytes_stream.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        yield Err(ClientError::HttpError(e));
+                        return;
+                    }
+                };
+                
+                let elements = match parser.push(&chunk) {
+                    Ok(elements) => elements,
+                    Err(e) => {
+                        yield Err(e);
+                        return;
+                    }
+                };
+                
+                for element in elements {
+                    count += 1;
+                    yield serde_json::from_slice::<ModelInfo>(&element).map_err(ClientError::JsonParseError);
+                }
+            }
+            
+            match parser.finish() {
+                Ok(()) => info!("Streamed {} models", count),
+                Err(e) => yield Err(e),
+            }
+        })
+    }
+
+    /// Fetch and wrap the model list from the given URL
+    async fn fetch_model_list(&self, url: String) -> ClientResult<WithMeta<ModelListResponse>> {
         let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         // The server returns a simple array of ModelInfo, not a wrapped response
         let models: Vec<ModelInfo> = response.json().await?;
@@ -103,28 +345,41 @@ impl LmoClient {
             has_more: false, // We don't have pagination info from server
         };
         
-        Ok(response)
+        Ok(WithMeta { body: response, meta })
     }
 
     /// List local models
     pub async fn list_local_models(&self) -> ClientResult<LocalModelsResponse> {
+        self.list_local_models_with_meta().await.map(WithMeta::into_body)
+    }
+
+    /// List local models, with response metadata
+    pub async fn list_local_models_with_meta(&self) -> ClientResult<WithMeta<LocalModelsResponse>> {
         debug!("Listing local models");
         
         let url = self.config.api_url(Endpoints::MODELS_LIST_LOCAL)?;
         let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let local_models: LocalModelsResponse = response.json().await?;
         info!("Listed {} local models", local_models.models.len());
         
-        Ok(local_models)
+        Ok(WithMeta { body: local_models, meta })
     }
 
     /// Load a model
     pub async fn load_model(&self, request: LoadModelRequest) -> ClientResult<LoadModelResponse> {
+        self.load_model_with_meta(request).await.map(WithMeta::into_body)
+    }
+
+    /// Load a model, with response metadata
+    pub async fn load_model_with_meta(&self, request: LoadModelRequest) -> ClientResult<WithMeta<LoadModelResponse>> {
         info!("Loading model: {}", request.model_id);
         
         let url = self.config.api_url(Endpoints::MODELS_LOAD)?;
-        let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await;
+        let response = self.with_model_suggestions(&request.model_id, response).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let load_response: LoadModelResponse = response.json().await?;
         
@@ -145,15 +400,21 @@ impl LmoClient {
                 load_response.message);
         }
         
-        Ok(load_response)
+        Ok(WithMeta { body: load_response, meta })
     }
 
     /// Unload a model
     pub async fn unload_model(&self, request: UnloadModelRequest) -> ClientResult<UnloadModelResponse> {
+        self.unload_model_with_meta(request).await.map(WithMeta::into_body)
+    }
+
+    /// Unload a model, with response metadata
+    pub async fn unload_model_with_meta(&self, request: UnloadModelRequest) -> ClientResult<WithMeta<UnloadModelResponse>> {
         info!("Unloading model: {}", request.instance_id);
         
         let url = self.config.api_url(Endpoints::MODELS_UNLOAD)?;
         let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let unload_response: UnloadModelResponse = response.json().await?;
         
@@ -169,41 +430,59 @@ impl LmoClient {
                 unload_response.message);
         }
         
-        Ok(unload_response)
+        Ok(WithMeta { body: unload_response, meta })
     }
 
     /// Get model status
     pub async fn model_status(&self, model_id: &str) -> ClientResult<ModelStatusInfo> {
+        self.model_status_with_meta(model_id).await.map(WithMeta::into_body)
+    }
+
+    /// Get model status, with response metadata
+    pub async fn model_status_with_meta(&self, model_id: &str) -> ClientResult<WithMeta<ModelStatusInfo>> {
         debug!("Getting status for model: {}", model_id);
         
         let url = self.config.api_url(&format!("{}/{}", Endpoints::MODELS_STATUS, model_id))?;
         let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let status: ModelStatusInfo = response.json().await?;
         debug!("Model status: {} - {}", model_id, status.status);
         
-        Ok(status)
+        Ok(WithMeta { body: status, meta })
     }
 
     /// Get all loaded models
     pub async fn loaded_models(&self) -> ClientResult<Vec<ModelStatusInfo>> {
+        self.loaded_models_with_meta().await.map(WithMeta::into_body)
+    }
+
+    /// Get all loaded models, with response metadata
+    pub async fn loaded_models_with_meta(&self) -> ClientResult<WithMeta<Vec<ModelStatusInfo>>> {
         debug!("Getting loaded models");
         
         let url = self.config.api_url(Endpoints::MODELS_LOADED)?;
         let response = self.make_request(reqwest::Method::GET, url, None::<&()>).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let models: Vec<ModelStatusInfo> = response.json().await?;
         info!("Found {} loaded models", models.len());
         
-        Ok(models)
+        Ok(WithMeta { body: models, meta })
     }
 
     /// Download a model from a remote repository (legacy synchronous method)
     pub async fn download_model(&self, request: DownloadModelRequest) -> ClientResult<DownloadModelResponse> {
+        self.download_model_with_meta(request).await.map(WithMeta::into_body)
+    }
+
+    /// Download a model synchronously, with response metadata
+    pub async fn download_model_with_meta(&self, request: DownloadModelRequest) -> ClientResult<WithMeta<DownloadModelResponse>> {
         info!("Downloading model (legacy): {}", request.model_name);
         
         let url = self.config.api_url(Endpoints::MODELS_DOWNLOAD_LEGACY)?;
         let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let download_response: DownloadModelResponse = response.json().await?;
         
@@ -224,24 +503,74 @@ impl LmoClient {
                 download_response.message);
         }
         
-        Ok(download_response)
+        Ok(WithMeta { body: download_response, meta })
     }
 
     /// Create a chat completion (non-streaming)
     pub async fn chat_completion(&self, request: ChatCompletionRequest) -> ClientResult<ChatCompletionResponse> {
+        self.chat_completion_with_priority(request, RequestPriority::Interactive).await
+    }
+
+    /// Create a chat completion, with response metadata
+    pub async fn chat_completion_with_meta(
+        &self,
+        request: ChatCompletionRequest,
+    ) -> ClientResult<WithMeta<ChatCompletionResponse>> {
+        self.send_chat_completion(request, RequestPriority::Interactive).await
+    }
+
+    /// Create a chat completion, queued with the given priority when `max_in_flight` is set
+    pub async fn chat_completion_with_priority(
+        &self,
+        request: ChatCompletionRequest,
+        priority: RequestPriority,
+    ) -> ClientResult<ChatCompletionResponse> {
+        self.send_chat_completion(request, priority).await.map(WithMeta::into_body)
+    }
+
+    /// Send a non-streaming chat completion request
+    async fn send_chat_completion(
+        &self,
+        request: ChatCompletionRequest,
+        priority: RequestPriority,
+    ) -> ClientResult<WithMeta<ChatCompletionResponse>> {
         debug!("Creating chat completion for model: {}", request.model);
         
         let url = self.config.api_url(Endpoints::CHAT_COMPLETIONS)?;
-        let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let permit = self.acquire_inference_permit(priority).await?;
+        let response = match self.make_request(reqwest::Method::POST, url, Some(&request)).await {
+            Ok(response) => response,
+            Err(e) => {
+                // Free the inference slot before the suggestion lookup lists models
+                drop(permit);
+                return Err(self.add_model_suggestions(&request.model, e).await);
+            }
+        };
+        let meta = ResponseMeta::from_response(&response);
         
-        let completion: ChatCompletionResponse = response.json().await?;
+        let mut completion: ChatCompletionResponse = response.json().await?;
         info!("Chat completion created with {} choices", completion.choices.len());
         
-        Ok(completion)
+        if !self.guardrails.is_empty() {
+            for choice in &mut completion.choices {
+                choice.message.content = self.guardrails.apply(std::mem::take(&mut choice.message.content))?;
+            }
+        }
+        
+        Ok(WithMeta { body: completion, meta })
     }
 
     /// Create a streaming chat completion
     pub async fn chat_completion_stream(&self, request: ChatCompletionRequest) -> ClientResult<ChatCompletionStream> {
+        self.chat_completion_stream_with_priority(request, RequestPriority::Interactive).await
+    }
+
+    /// Create a streaming chat completion, queued with the given priority when `max_in_flight` is set
+    pub async fn chat_completion_stream_with_priority(
+        &self,
+        request: ChatCompletionRequest,
+        priority: RequestPriority,
+    ) -> ClientResult<ChatCompletionStream> {
         debug!("Creating streaming chat completion for model: {}", request.model);
         
         // Ensure streaming is enabled in request
@@ -249,9 +578,52 @@ impl LmoClient {
         stream_request.stream = Some(true);
         
         let url = self.config.api_url(Endpoints::CHAT_COMPLETIONS_STREAM)?;
-        let response = self.make_request_stream(reqwest::Method::POST, url, Some(&stream_request)).await?;
+        // The permit is held by the stream until it is dropped
+        let permit = self.acquire_inference_permit(priority).await?;
+        let response = match self
+            .make_request_stream(reqwest::Method::POST, url, Some(&stream_request), Some(self.config.stream_timeout))
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                drop(permit);
+                return Err(self.add_model_suggestions(&stream_request.model, e).await);
+            }
+        };
         
-        Ok(ChatCompletionStream::new(response))
+        Ok(ChatCompletionStream::new(response)
+            .with_permit(permit)
+            .with_guardrails(self.guardrails.clone()))
+    }
+
+    /// Continue an interrupted completion, sending the partial answer as an assistant prefill
+    ///
+    /// The returned response contains only the continuation, to be appended to `partial_text`.
+    pub async fn chat_completion_continue<S: Into<String>>(
+        &self,
+        request: ChatCompletionRequest,
+        partial_text: S,
+    ) -> ClientResult<ChatCompletionResponse> {
+        let partial_text = partial_text.into();
+        info!(
+            "Continuing chat completion for model: {} ({} chars received)",
+            request.model,
+            partial_text.len()
+        );
+        
+        self.chat_completion(Self::continuation_request(request, partial_text)).await
+    }
+
+    /// Build a request that asks the model to continue from a partial answer
+    fn continuation_request(request: ChatCompletionRequest, partial_text: String) -> ChatCompletionRequest {
+        let mut continue_request = request;
+        continue_request.stream = None;
+        continue_request.messages.push(ChatMessage {
+            role: "assistant".to_string(),
+            content: partial_text,
+            name: None,
+        });
+        continue_request
     }
 
     /// Create a chat request builder
@@ -259,6 +631,18 @@ impl LmoClient {
         ChatRequestBuilder::new()
     }
 
+    /// Build a request with the JSON body, if any, and all registered hooks applied
+    fn build_request<T: serde::Serialize>(&self, method: reqwest::Method, url: &str, body: Option<&T>) -> RequestBuilder {
+        let mut request_builder = self.client.request(method, url);
+        
+        // Add JSON body if provided
+        if let Some(body) = body {
+            request_builder = request_builder.json(body);
+        }
+        
+        self.request_hooks.0.iter().fold(request_builder, |builder, hook| hook(builder))
+    }
+
     /// Make a JSON HTTP request with error handling and retries
     pub(crate) async fn make_request<T: serde::Serialize, U: reqwest::IntoUrl>(
         &self,
@@ -269,12 +653,9 @@ impl LmoClient {
         let mut retries = 0;
         
         loop {
-            let mut request_builder = self.client.request(method.clone(), url.as_str());
-            
-            // Add JSON body if provided
-            if let Some(body) = body {
-                request_builder = request_builder.json(body);
-            }
+            let request_builder = self
+                .build_request(method.clone(), url.as_str(), body)
+                .timeout(self.config.timeout);
             
             // Log request if enabled
             if self.config.enable_logging {
@@ -285,6 +666,7 @@ impl LmoClient {
             match request_builder.send().await {
                 Ok(response) => {
                     let status = response.status();
+                    let rate_limit = self.record_rate_limit(&response);
                     
                     if self.config.enable_logging {
                         debug!("Response status: {}", status);
@@ -298,9 +680,21 @@ impl LmoClient {
                         let error = ClientError::from_response(status.as_u16(), error_body);
                         
                         if error.is_retryable() && retries < self.config.max_retries {
+                            // Wait out the server's Retry-After or exhausted rate-limit budget,
+                            // but fail fast instead of parking the caller for too long
+                            let retry_after = rate_limit.and_then(|r| r.wait_time()).unwrap_or_default();
+                            if retry_after > self.config.max_retry_wait {
+                                return Err(ClientError::RateLimited(format!(
+                                    "server asked to wait {}s before retrying, more than the {}s limit: {}",
+                                    retry_after.as_secs(),
+                                    self.config.max_retry_wait.as_secs(),
+                                    error
+                                )));
+                            }
+                            
                             warn!("Retryable error (attempt {}): {}", retries + 1, error);
                             retries += 1;
-                            tokio::time::sleep(self.config.retry_delay).await;
+                            tokio::time::sleep(retry_after.max(self.config.retry_delay)).await;
                             continue;
                         } else {
                             return Err(error);
@@ -324,23 +718,23 @@ impl LmoClient {
     }
 
     /// Make a streaming HTTP request
-    async fn make_request_stream<T: serde::Serialize, U: reqwest::IntoUrl>(
+    ///
+    /// `timeout` bounds the whole stream; without one the stream only ends
+    /// early if no data arrives for the configured stream timeout.
+    pub(crate) async fn make_request_stream<T: serde::Serialize, U: reqwest::IntoUrl>(
         &self,
         method: reqwest::Method,
         url: U,
         body: Option<&T>,
+        timeout: Option<std::time::Duration>,
     ) -> ClientResult<Response> {
-        let mut request_builder = self.client.request(method.clone(), url.as_str());
-        
-        // Add JSON body if provided
-        if let Some(body) = body {
-            request_builder = request_builder.json(body);
-        }
-        
-        // Add streaming headers
-        request_builder = request_builder
-            .header("Accept", "text/event-stream")
+        // Add streaming headers; streams outlive the regular request timeout
+        let mut request_builder = self.build_request(method.clone(), url.as_str(), body)
+            .header("Accept", "text/event-stream, application/x-ndjson;q=0.9")
             .header("Cache-Control", "no-cache");
+        if let Some(timeout) = timeout {
+            request_builder = request_builder.timeout(timeout);
+        }
         
         if self.config.enable_logging {
             debug!("Making streaming {} request to: {}", method, url.as_str());
@@ -348,6 +742,7 @@ impl LmoClient {
         
         let response = request_builder.send().await?;
         let status = response.status();
+        self.record_rate_limit(&response);
         
         if status.is_success() {
             Ok(response)
@@ -386,6 +781,98 @@ mod tests {
         assert_eq!(client.config().server_url, "http://example.com:8080");
     }
 
+    #[test]
+    fn test_continuation_request() {
+        let request = ChatRequestBuilder::new()
+            .model("test-model")
+            .message("user", "Count to ten")
+            .stream(true)
+            .build();
+
+        let request = LmoClient::continuation_request(request, "1, 2, 3,".to_string());
+        assert_eq!(request.stream, None);
+        assert_eq!(request.messages.len(), 2);
+        assert_eq!(request.messages[1].role, "assistant");
+        assert_eq!(request.messages[1].content, "1, 2, 3,");
+    }
+
+    #[test]
+    fn test_default_headers() {
+        let config = ClientConfig::default()
+            .with_api_key("secret")
+            .with_tag("team", "ml")
+            .with_header("X-Environment", "staging");
+
+        let headers = LmoClient::default_headers(&config).unwrap();
+        assert_eq!(headers.get(AUTHORIZATION).unwrap(), "Bearer secret");
+        assert_eq!(headers.get(CLIENT_TAGS_HEADER).unwrap(), "team=ml");
+        assert_eq!(headers.get("x-environment").unwrap(), "staging");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_requests_carry_auth_and_hooks() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\ncontent-length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request).to_lowercase()
+        });
+
+        let config = ClientConfig::new(format!("http://{}", addr)).unwrap().with_api_key("secret");
+        let client = LmoClient::with_config(config)
+            .unwrap()
+            .with_request_hook(|request| request.header("x-signed", "yes"));
+
+        let progress = client.download_progress_stream(&"dl-1".to_string()).await.unwrap();
+        let events: Vec<_> = progress.into_stream().collect().await;
+        assert!(events.is_empty());
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("get /v1/models/download/dl-1/progress"));
+        assert!(request.contains("authorization: bearer secret"));
+        assert!(request.contains("x-signed: yes"));
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_fails_fast() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 429 Too Many Requests\r\nretry-after: 3600\r\ncontent-length: 4\r\n\r\nbusy")
+                .await
+                .unwrap();
+        });
+
+        let config = ClientConfig::new(format!("http://{}", addr))
+            .unwrap()
+            .with_max_retry_wait(std::time::Duration::from_secs(5));
+        let client = LmoClient::with_config(config).unwrap();
+
+        let started = std::time::Instant::now();
+        let error = client.health().await.unwrap_err();
+        assert!(matches!(error, ClientError::RateLimited(ref message) if message.contains("the 5s limit")));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        server.await.unwrap();
+    }
+
     #[test]
     fn test_client_with_invalid_url() {
         let client = LmoClient::with_url("not-a-valid-url");
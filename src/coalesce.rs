@@ -0,0 +1,108 @@
+/*!
+ * Request Coalescing
+ *
+ * Shares a single in-flight request between concurrent callers of the same
+ * idempotent GET, so bursts of identical calls hit the server only once.
+ */
+
+use futures::future::{BoxFuture, FutureExt, Shared};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+use crate::error::{ClientError, ClientResult};
+
+type SharedRequest<T> = Shared<BoxFuture<'static, Result<T, Arc<ClientError>>>>;
+
+/// Coalesces concurrent calls into one shared in-flight request
+pub(crate) struct Coalescer<T> {
+    in_flight: Mutex<Option<SharedRequest<T>>>,
+}
+
+impl<T> Coalescer<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// Create an idle coalescer
+    pub fn new() -> Self {
+        Self {
+            in_flight: Mutex::new(None),
+        }
+    }
+
+    /// Join the in-flight request, or start one with `request` if none is running
+    pub async fn run<F, Fut>(&self, request: F) -> ClientResult<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = ClientResult<T>> + Send + 'static,
+    {
+        let shared = {
+            let mut guard = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+            match guard.as_ref() {
+                Some(shared) => {
+                    debug!("Joining in-flight request");
+                    shared.clone()
+                }
+                None => {
+                    let shared = request().map(|result| result.map_err(Arc::new)).boxed().shared();
+                    *guard = Some(shared.clone());
+                    shared
+                }
+            }
+        };
+
+        let result = shared.clone().await;
+
+        // The first caller to observe completion frees the slot for the next request
+        let mut guard = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.as_ref().is_some_and(|current| current.ptr_eq(&shared)) {
+            *guard = None;
+        }
+
+        result.map_err(|e| e.duplicate())
+    }
+}
+
+impl<T> fmt::Debug for Coalescer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let busy = self.in_flight.lock().map(|guard| guard.is_some()).unwrap_or(false);
+        f.debug_struct("Coalescer").field("in_flight", &busy).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_request() {
+        let coalescer = Arc::new(Coalescer::<u32>::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let request = |calls: Arc<AtomicUsize>| {
+            move || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(7)
+            }
+        };
+
+        let (a, b, c) = tokio::join!(
+            coalescer.run(request(calls.clone())),
+            coalescer.run(request(calls.clone())),
+            coalescer.run(request(calls.clone())),
+        );
+        assert_eq!((a.unwrap(), b.unwrap(), c.unwrap()), (7, 7, 7));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Once finished, the next call starts a fresh request
+        let error = coalescer
+            .run(|| async { Err(ClientError::NetworkError("down".to_string())) })
+            .await;
+        assert!(matches!(error, Err(ClientError::NetworkError(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -5,7 +5,9 @@
  */
 
 use crate::error::{ClientError, ClientResult};
+use reqwest::header::{HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 use url::Url;
 
@@ -30,8 +32,40 @@ pub struct ClientConfig {
     /// Delay between retries
     pub retry_delay: Duration,
     
+    /// Longest server-requested wait (`Retry-After` or rate-limit reset) honored before retrying
+    #[serde(default = "default_max_retry_wait")]
+    pub max_retry_wait: Duration,
+    
+    /// Timeout for chat streams, and the longest any stream may go without data
+    #[serde(default = "default_stream_timeout")]
+    pub stream_timeout: Duration,
+    
     /// Enable request/response logging
     pub enable_logging: bool,
+    
+    /// Maximum number of concurrent inference calls (unlimited if None)
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+    
+    /// Application identifier appended to the user agent (e.g. "my-app/1.2")
+    #[serde(default)]
+    pub user_agent_suffix: Option<String>,
+    
+    /// Tags sent with every request for usage attribution (e.g. team, environment)
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub default_headers: BTreeMap<String, String>,
+}
+
+fn default_stream_timeout() -> Duration {
+    Duration::from_secs(120)
+}
+
+fn default_max_retry_wait() -> Duration {
+    Duration::from_secs(60)
 }
 
 impl Default for ClientConfig {
@@ -43,7 +77,13 @@ impl Default for ClientConfig {
             api_key: None,
             max_retries: 3,
             retry_delay: Duration::from_millis(1000),
+            max_retry_wait: default_max_retry_wait(),
+            stream_timeout: default_stream_timeout(),
             enable_logging: true,
+            max_in_flight: None,
+            user_agent_suffix: None,
+            tags: BTreeMap::new(),
+            default_headers: BTreeMap::new(),
         }
     }
 }
@@ -68,11 +108,42 @@ impl ClientConfig {
             return Err(ClientError::ConfigError("Timeout must be greater than 0".to_string()));
         }
 
+        if self.stream_timeout.is_zero() {
+            return Err(ClientError::ConfigError("Stream timeout must be greater than 0".to_string()));
+        }
+
         // Validate retry settings
         if self.max_retries > 10 {
             return Err(ClientError::ConfigError("Max retries cannot exceed 10".to_string()));
         }
 
+        // Validate custom headers
+        for (name, value) in &self.default_headers {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| ClientError::ConfigError(format!("Invalid header name '{}': {}", name, e)))?;
+            HeaderValue::from_str(value)
+                .map_err(|e| ClientError::ConfigError(format!("Invalid value for header '{}': {}", name, e)))?;
+        }
+        
+        // Validate tags (sent as a single header value)
+        for (key, value) in &self.tags {
+            if key.is_empty() || [key, value].iter().any(|part| part.contains([',', '='])) {
+                return Err(ClientError::ConfigError(format!(
+                    "Invalid client tag '{}={}': keys must be non-empty and tags cannot contain ',' or '='",
+                    key, value
+                )));
+            }
+        }
+        if let Some(tags) = self.tags_header_value() {
+            HeaderValue::from_str(&tags)
+                .map_err(|e| ClientError::ConfigError(format!("Invalid client tags: {}", e)))?;
+        }
+
+        // Validate concurrency limit
+        if self.max_in_flight == Some(0) {
+            return Err(ClientError::ConfigError("Max in-flight requests must be greater than 0".to_string()));
+        }
+
         Ok(())
     }
 
@@ -88,6 +159,42 @@ impl ClientConfig {
         }
     }
 
+    /// Get the user agent including the application suffix, if any
+    pub fn effective_user_agent(&self) -> String {
+        match &self.user_agent_suffix {
+            Some(suffix) if !suffix.is_empty() => format!("{} {}", self.user_agent, suffix),
+            _ => self.user_agent.clone(),
+        }
+    }
+
+    /// Get the tags formatted for the `X-Client-Tags` header ("key=value,key=value")
+    pub fn tags_header_value(&self) -> Option<String> {
+        if self.tags.is_empty() {
+            return None;
+        }
+        
+        let tags: Vec<String> = self.tags.iter().map(|(key, value)| format!("{}={}", key, value)).collect();
+        Some(tags.join(","))
+    }
+
+    /// Append an application identifier to the user agent
+    pub fn with_user_agent_suffix<S: Into<String>>(mut self, suffix: S) -> Self {
+        self.user_agent_suffix = Some(suffix.into());
+        self
+    }
+
+    /// Add a tag sent with every request; keys and values cannot contain `,` or `=`
+    pub fn with_tag<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add a header sent with every request
+    pub fn with_header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.default_headers.insert(name.into(), value.into());
+        self
+    }
+
     /// Set API key for authentication
     pub fn with_api_key<S: Into<String>>(mut self, api_key: S) -> Self {
         self.api_key = Some(api_key.into());
@@ -100,6 +207,36 @@ impl ClientConfig {
         self
     }
 
+    /// Set timeout for streaming requests
+    pub fn with_stream_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_timeout = timeout;
+        self
+    }
+
+    /// Set maximum number of retries for failed requests
+    pub fn with_max_retries(mut self, max_retries: usize) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set delay between retries
+    pub fn with_retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.retry_delay = retry_delay;
+        self
+    }
+
+    /// Set the longest server-requested wait to honor; longer waits fail as rate limited
+    pub fn with_max_retry_wait(mut self, max_retry_wait: Duration) -> Self {
+        self.max_retry_wait = max_retry_wait;
+        self
+    }
+
+    /// Limit the number of concurrent inference calls
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = Some(max_in_flight);
+        self
+    }
+
     /// Enable or disable logging
     pub fn with_logging(mut self, enable: bool) -> Self {
         self.enable_logging = enable;
@@ -163,6 +300,61 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_retry_overrides() {
+        let config = ClientConfig::default()
+            .with_timeout(Duration::from_secs(600))
+            .with_stream_timeout(Duration::from_secs(3600))
+            .with_max_retries(5)
+            .with_retry_delay(Duration::from_millis(250))
+            .with_max_retry_wait(Duration::from_secs(5));
+
+        assert!(config.validate().is_ok());
+        assert_eq!(config.max_retries, 5);
+        assert_eq!(config.retry_delay, Duration::from_millis(250));
+        assert_eq!(config.max_retry_wait, Duration::from_secs(5));
+        assert_eq!(config.stream_timeout, Duration::from_secs(3600));
+
+        let config = ClientConfig::default().with_max_retries(11);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_max_in_flight() {
+        let config = ClientConfig::default();
+        assert_eq!(config.max_in_flight, None);
+
+        let config = config.with_max_in_flight(4);
+        assert!(config.validate().is_ok());
+        assert_eq!(config.max_in_flight, Some(4));
+
+        let config = ClientConfig::default().with_max_in_flight(0);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_user_agent_and_tags() {
+        let config = ClientConfig::default();
+        assert_eq!(config.effective_user_agent(), config.user_agent);
+        assert_eq!(config.tags_header_value(), None);
+
+        let config = config
+            .with_user_agent_suffix("dashboard/2.0")
+            .with_tag("team", "ml")
+            .with_tag("env", "prod")
+            .with_header("X-Trace", "on");
+        assert!(config.validate().is_ok());
+        assert!(config.effective_user_agent().ends_with(" dashboard/2.0"));
+        assert_eq!(config.tags_header_value().as_deref(), Some("env=prod,team=ml"));
+
+        let config = ClientConfig::default().with_header("bad header", "x");
+        assert!(config.validate().is_err());
+
+        assert!(ClientConfig::default().with_tag("team", "ml,env=prod").validate().is_err());
+        assert!(ClientConfig::default().with_tag("a=b", "c").validate().is_err());
+        assert!(ClientConfig::default().with_tag("", "c").validate().is_err());
+    }
+
     #[test]
     fn test_invalid_url() {
         let result = ClientConfig::new("not-a-url");
@@ -0,0 +1,486 @@
+/*!
+ * Conversation State
+ * 
+ * Message history for multi-turn chats, with an optional seed that is
+ * propagated to every request for reproducible runs, compaction of old
+ * turns into a model-written summary, replay against another model, and
+ * recovery from context-length errors by trimming history.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use tracing::{info, warn};
+
+use crate::client::LmoClient;
+use crate::error::{ClientError, ClientResult};
+use crate::models::ChatRequestBuilder;
+use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse, ChatMessage};
+
+/// Instruction appended to the old turns when asking for a summary
+const COMPACT_PROMPT: &str = "Summarize the conversation so far in a few sentences. \
+Keep the facts, decisions and open questions needed to continue it.";
+
+/// Rough characters-per-token ratio used to estimate savings
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Attempts at trimming history after a context-length error before giving up
+const MAX_TRUNCATION_RETRIES: usize = 3;
+
+/// How to trim a conversation's history when it no longer fits the model's context
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationPolicy {
+    /// Return the context-length error unchanged
+    #[default]
+    Error,
+    /// Drop the oldest user/assistant turns after the leading system prompts
+    DropOldest,
+    /// Summarize all but the last `keep_last` messages with the model
+    Summarize { keep_last: usize },
+}
+
+/// Outcome of compacting a conversation
+#[derive(Debug, Clone)]
+pub struct CompactSummary {
+    /// Number of messages replaced by the summary
+    pub replaced_messages: usize,
+    /// Summary that replaced them
+    pub summary: String,
+    /// Estimated prompt tokens saved per request (about four characters per token)
+    pub estimated_tokens_saved: usize,
+}
+
+/// Multi-turn conversation with a target model and optional fixed seed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Conversation {
+    /// Model every request is sent to
+    pub model: String,
+    /// Full message history, oldest first
+    pub messages: Vec<ChatMessage>,
+    /// Sampling seed sent with every request, if fixed
+    pub seed: Option<u64>,
+    /// How history is trimmed when it no longer fits the model's context
+    #[serde(default)]
+    pub truncation: TruncationPolicy,
+}
+
+impl Conversation {
+    /// Create an empty conversation for the given model
+    pub fn new<S: Into<String>>(model: S) -> Self {
+        Self {
+            model: model.into(),
+            messages: vec![],
+            seed: None,
+            truncation: TruncationPolicy::default(),
+        }
+    }
+
+    /// Fix the sampling seed for every request in this conversation
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Set how history is trimmed when a request exceeds the model's context
+    pub fn with_truncation(mut self, policy: TruncationPolicy) -> Self {
+        self.truncation = policy;
+        self
+    }
+
+    /// Start the conversation with a system prompt
+    pub fn with_system<S: Into<String>>(mut self, content: S) -> Self {
+        self.push("system", content);
+        self
+    }
+
+    /// Append a message with the given role
+    pub fn push<R: Into<String>, S: Into<String>>(&mut self, role: R, content: S) {
+        self.messages.push(ChatMessage {
+            role: role.into(),
+            content: content.into(),
+            name: None,
+        });
+    }
+
+    /// Append a user message
+    pub fn push_user<S: Into<String>>(&mut self, content: S) {
+        self.push("user", content);
+    }
+
+    /// Append an assistant message
+    pub fn push_assistant<S: Into<String>>(&mut self, content: S) {
+        self.push("assistant", content);
+    }
+
+    /// Messages eligible for compaction: everything after the leading system
+    /// prompts except the last `keep_last` messages
+    fn compactable_range(&self, keep_last: usize) -> Range<usize> {
+        let start = self.messages.iter().take_while(|m| m.role == "system").count();
+        let end = self.messages.len().saturating_sub(keep_last).max(start);
+        start..end
+    }
+
+    /// Remove a range of messages and append their summary to the leading system prompt
+    ///
+    /// Chat templates typically accept system messages only at the start, so
+    /// the summary joins the last leading system message, or becomes the
+    /// system prompt if there is none.
+    fn replace_with_summary(&mut self, range: Range<usize>, summary: &str) {
+        let summary = format!("Summary of the earlier conversation: {}", summary);
+        self.messages.drain(range);
+
+        let leading_system = self.messages.iter().take_while(|m| m.role == "system").count();
+        match leading_system.checked_sub(1) {
+            Some(last) => {
+                let prompt = &mut self.messages[last].content;
+                prompt.push_str("\n\n");
+                prompt.push_str(&summary);
+            }
+            None => self.messages.insert(
+                0,
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: summary,
+                    name: None,
+                },
+            ),
+        }
+    }
+
+    /// Drop the oldest whole turns after the system prompts, keeping the last one
+    ///
+    /// A turn is a user message and the replies up to the next user message,
+    /// so the remaining history still starts with a user message. Removes
+    /// enough turns to free roughly `excess_tokens` (at least one turn) and
+    /// returns how many messages were removed.
+    fn drop_oldest(&mut self, excess_tokens: Option<u32>) -> usize {
+        let start = self.messages.iter().take_while(|m| m.role == "system").count();
+        let target_chars = excess_tokens.unwrap_or(0) as usize * CHARS_PER_TOKEN;
+
+        let mut end = start;
+        let mut removed_chars = 0;
+        for (index, message) in self.messages.iter().enumerate().skip(start + 1) {
+            if message.role != "user" {
+                continue;
+            }
+            removed_chars += self.messages[end..index].iter().map(|m| m.content.len()).sum::<usize>();
+            end = index;
+            if removed_chars >= target_chars {
+                break;
+            }
+        }
+
+        self.messages.drain(start..end);
+        end - start
+    }
+
+    /// Build a chat request carrying the full history and the conversation seed
+    pub fn request(&self) -> ChatCompletionRequest {
+        let mut builder = ChatRequestBuilder::new().model(self.model.clone());
+        if let Some(seed) = self.seed {
+            builder = builder.seed(seed);
+        }
+
+        let mut request = builder.build();
+        request.messages = self.messages.clone();
+        request
+    }
+}
+
+impl LmoClient {
+    /// Summarize all but the last `keep_last` messages and replace them with the summary
+    ///
+    /// Leading system prompts are kept and the summary is appended to them.
+    /// Returns `None` if there are fewer than two messages to summarize.
+    pub async fn compact_conversation(
+        &self,
+        conversation: &mut Conversation,
+        keep_last: usize,
+    ) -> ClientResult<Option<CompactSummary>> {
+        let range = conversation.compactable_range(keep_last);
+        if range.len() < 2 {
+            return Ok(None);
+        }
+
+        let mut request = conversation.request();
+        request.messages = conversation.messages[range.clone()].to_vec();
+        request.messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: COMPACT_PROMPT.to_string(),
+            name: None,
+        });
+
+        let response = self.chat_completion(request).await?;
+        let summary = response
+            .choices
+            .first()
+            .map(|choice| choice.message.content.trim().to_string())
+            .filter(|summary| !summary.is_empty())
+            .ok_or_else(|| ClientError::InvalidResponse("Model returned an empty summary".to_string()))?;
+
+        let replaced_chars: usize = conversation.messages[range.clone()].iter().map(|m| m.content.len()).sum();
+        let replaced_messages = range.len();
+        conversation.replace_with_summary(range, &summary);
+
+        let estimated_tokens_saved = replaced_chars.saturating_sub(summary.len()) / CHARS_PER_TOKEN;
+        info!(
+            "Compacted {} messages into a summary (~{} tokens saved)",
+            replaced_messages, estimated_tokens_saved
+        );
+
+        Ok(Some(CompactSummary {
+            replaced_messages,
+            summary,
+            estimated_tokens_saved,
+        }))
+    }
+
+    /// Create a chat completion for the conversation's history
+    ///
+    /// When the server reports the context length exceeded, the history is
+    /// trimmed in place according to the conversation's truncation policy
+    /// and the request retried.
+    pub async fn conversation_completion(
+        &self,
+        conversation: &mut Conversation,
+    ) -> ClientResult<ChatCompletionResponse> {
+        let mut attempt = 0;
+
+        loop {
+            let error = match self.chat_completion(conversation.request()).await {
+                Err(e @ ClientError::ContextLengthExceeded { .. }) => e,
+                result => return result,
+            };
+
+            attempt += 1;
+            if attempt > MAX_TRUNCATION_RETRIES {
+                return Err(error);
+            }
+
+            let excess_tokens = match &error {
+                ClientError::ContextLengthExceeded { requested_tokens, context_limit, .. } => requested_tokens
+                    .zip(*context_limit)
+                    .map(|(requested, limit)| requested.saturating_sub(limit)),
+                _ => None,
+            };
+
+            let trimmed = match conversation.truncation {
+                TruncationPolicy::Error => 0,
+                TruncationPolicy::DropOldest => conversation.drop_oldest(excess_tokens),
+                TruncationPolicy::Summarize { keep_last } => {
+                    match self.compact_conversation(conversation, keep_last).await {
+                        Ok(summary) => summary.map_or(0, |summary| summary.replaced_messages),
+                        // The old turns are too long to summarize in one request
+                        Err(ClientError::ContextLengthExceeded { .. }) => {
+                            warn!("Summary request exceeded the context as well; dropping the oldest turns");
+                            conversation.drop_oldest(excess_tokens)
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+            };
+
+            if trimmed == 0 {
+                return Err(error);
+            }
+            warn!("{}; trimmed {} messages from the history and retrying", error, trimmed);
+        }
+    }
+
+    /// Re-run every user turn of a conversation, optionally against another model
+    ///
+    /// System prompts and user messages are kept; assistant replies are
+    /// regenerated, each one seeing the new replies before it. The history is
+    /// trimmed by the conversation's truncation policy when it outgrows the
+    /// model's context.
+    pub async fn replay_conversation(
+        &self,
+        conversation: &Conversation,
+        model: Option<&str>,
+    ) -> ClientResult<Conversation> {
+        let mut replay = Conversation {
+            model: model.unwrap_or(&conversation.model).to_string(),
+            messages: Vec::new(),
+            seed: conversation.seed,
+            truncation: conversation.truncation,
+        };
+        info!("Replaying conversation against {}", replay.model);
+
+        for message in &conversation.messages {
+            match message.role.as_str() {
+                "assistant" => continue,
+                "user" => {
+                    replay.messages.push(message.clone());
+                    let response = self.conversation_completion(&mut replay).await?;
+                    let reply = response
+                        .choices
+                        .first()
+                        .map(|choice| choice.message.content.clone())
+                        .ok_or_else(|| ClientError::InvalidResponse("Chat completion returned no choices".to_string()))?;
+                    replay.push_assistant(reply);
+                }
+                _ => replay.messages.push(message.clone()),
+            }
+        }
+
+        Ok(replay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_propagation() {
+        let mut conversation = Conversation::new("test-model")
+            .with_seed(42)
+            .with_system("You are terse.");
+        conversation.push_user("Hello");
+
+        let request = conversation.request();
+        assert_eq!(request.model, "test-model");
+        assert_eq!(request.seed, Some(42));
+        assert_eq!(request.messages.len(), 2);
+        assert_eq!(request.messages[0].role, "system");
+
+        conversation.push_assistant("Hi.");
+        conversation.push_user("Again");
+        assert_eq!(conversation.request().seed, Some(42));
+    }
+
+    #[test]
+    fn test_compaction_range() {
+        let mut conversation = Conversation::new("test-model").with_system("You are terse.");
+        for turn in 0..3 {
+            conversation.push_user(format!("Question {}", turn));
+            conversation.push_assistant(format!("Answer {}", turn));
+        }
+
+        let range = conversation.compactable_range(2);
+        assert_eq!(range, 1..5);
+        assert_eq!(conversation.compactable_range(10), 1..1);
+
+        conversation.replace_with_summary(range, "Two questions were answered.");
+        assert_eq!(conversation.messages.len(), 3);
+        assert_eq!(conversation.messages[0].role, "system");
+        assert!(conversation.messages[0].content.starts_with("You are terse."));
+        assert!(conversation.messages[0].content.ends_with("Two questions were answered."));
+        assert_eq!(conversation.messages[1].content, "Question 2");
+
+        let mut untitled = Conversation::new("test-model");
+        untitled.push_user("Question");
+        untitled.push_assistant("Answer");
+        untitled.push_user("Follow-up");
+        untitled.replace_with_summary(untitled.compactable_range(1), "A question was answered.");
+        assert_eq!(untitled.messages.len(), 2);
+        assert_eq!(untitled.messages[0].role, "system");
+        assert_eq!(untitled.messages[1].content, "Follow-up");
+    }
+
+    #[test]
+    fn test_drop_oldest() {
+        let mut conversation = Conversation::new("test-model")
+            .with_system("You are terse.")
+            .with_truncation(TruncationPolicy::DropOldest);
+        for turn in 0..3 {
+            conversation.push_user(format!("Question {}", turn));
+            conversation.push_assistant("a".repeat(40));
+        }
+        conversation.push_user("Last question");
+
+        assert_eq!(conversation.drop_oldest(None), 2);
+        assert_eq!(conversation.messages[0].role, "system");
+        assert_eq!(conversation.messages[1].content, "Question 1");
+
+        // 15 tokens is about 60 characters, more than one 50-character turn
+        assert_eq!(conversation.drop_oldest(Some(15)), 4);
+        assert_eq!(conversation.messages.len(), 2);
+        assert_eq!(conversation.messages[1].content, "Last question");
+        assert_eq!(conversation.drop_oldest(None), 0);
+    }
+
+    /// Answer one connection per canned `(status, body)` response, returning the request bodies
+    async fn serve(responses: Vec<(&'static str, String)>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for (status, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                let body_start = loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(end) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break end + 4;
+                    }
+                };
+                let head = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+                let length: usize = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map_or(0, |value| value.trim().parse().unwrap());
+                while request.len() < body_start + length {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                bodies.push(String::from_utf8_lossy(&request[body_start..]).into_owned());
+
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+            bodies
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_summarize_falls_back_when_summary_overflows() {
+        let overflow = "This model's maximum context length is 100 tokens. However, you requested 150 tokens".to_string();
+        let completion = serde_json::json!({
+            "id": "c-1", "object": "chat.completion", "created": 0, "model": "test-model",
+            "choices": [{"index": 0, "message": {"role": "assistant", "content": "ok"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2}
+        });
+        let (url, server) = serve(vec![
+            ("400 Bad Request", overflow.clone()),
+            ("400 Bad Request", overflow),
+            ("200 OK", completion.to_string()),
+        ])
+        .await;
+
+        let client = LmoClient::with_url(url).unwrap();
+        let mut conversation = Conversation::new("test-model")
+            .with_system("You are terse.")
+            .with_truncation(TruncationPolicy::Summarize { keep_last: 1 });
+        for turn in 0..2 {
+            conversation.push_user(format!("Question {}", turn));
+            conversation.push_assistant(format!("Answer {}", turn));
+        }
+        conversation.push_user("Last question");
+
+        let response = client.conversation_completion(&mut conversation).await.unwrap();
+        assert_eq!(response.choices[0].message.content, "ok");
+        assert_eq!(conversation.messages.len(), 2);
+        assert_eq!(conversation.messages[1].content, "Last question");
+
+        let bodies = server.await.unwrap();
+        assert!(bodies[1].contains("Summarize the conversation so far"));
+        assert!(!bodies[2].contains("Question 0"));
+    }
+
+    #[test]
+    fn test_unseeded_conversation() {
+        let conversation = Conversation::new("test-model");
+        assert_eq!(conversation.request().seed, None);
+    }
+}
@@ -7,41 +7,96 @@
 
 use futures::stream::Stream;
 use reqwest;
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tokio_stream::StreamExt;
 use tracing::{debug, error, info, warn};
 
 use crate::config::Endpoints;
 use crate::error::{ClientError, ClientResult};
+use crate::stream_parser::{StreamFormat, StreamFrame, StreamParser};
 use crate::models::{
-    DownloadControlRequest, DownloadControlResponse, DownloadEvent, DownloadId,
-    DownloadModelRequest, StartDownloadResponse,
+    DownloadControlRequest, DownloadControlResponse, DownloadEvent, DownloadEventType, DownloadId, DownloadProgress,
+    DownloadModelRequest, DownloadModelResponse, StartDownloadResponse,
 };
 use crate::client::LmoClient;
+use crate::meta::{ResponseMeta, WithMeta};
 
-/// Parsed SSE event types
-#[derive(Debug)]
-enum ParsedSseEvent {
-    /// Download progress event with JSON data
-    DownloadEvent(String),
-    /// Keep-alive event from Axum
-    KeepAlive,
-    /// Heartbeat event from server
-    Heartbeat,
+/// Progress of a single file within a multi-file model download
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FileProgress {
+    /// File name within the model repository (shard, tokenizer, config, ...)
+    pub filename: String,
+    /// Bytes downloaded so far
+    pub downloaded_bytes: u64,
+    /// Total file size, if known
+    pub total_bytes: Option<u64>,
+    /// Whether the file has finished downloading
+    #[serde(default)]
+    pub completed: bool,
+}
+
+impl FileProgress {
+    /// Fraction of the file downloaded (0.0-1.0), if the total size is known
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total_bytes {
+            Some(0) => Some(if self.completed { 1.0 } else { 0.0 }),
+            Some(total) => Some((self.downloaded_bytes as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+/// Download event together with per-file progress
+#[derive(Debug, Clone)]
+pub struct DetailedDownloadEvent {
+    /// The aggregate download event
+    pub event: DownloadEvent,
+    /// Per-file progress reported alongside the event
+    pub files: Vec<FileProgress>,
+}
+
+/// Per-file fields carried next to the aggregate event payload
+#[derive(Deserialize)]
+struct FileProgressFields {
+    #[serde(default)]
+    files: Vec<FileProgress>,
+}
+
+impl DetailedDownloadEvent {
+    /// Parse an event payload, reading the optional `files` array
+    fn from_json(json_data: &str) -> Result<Self, serde_json::Error> {
+        let event = serde_json::from_str::<DownloadEvent>(json_data)?;
+        let fields = serde_json::from_str::<FileProgressFields>(json_data)?;
+        Ok(Self {
+            event,
+            files: fields.files,
+        })
+    }
 }
 
 /// Download progress stream using Server-Sent Events
 pub struct DownloadProgressStream {
+    client: LmoClient,
     sse_url: String,
     download_id: DownloadId,
 }
 
 impl DownloadProgressStream {
-    /// Create a new download progress stream
+    /// Create a new download progress stream using a default-configured client
     pub fn new(sse_url: String, download_id: DownloadId) -> ClientResult<Self> {
-        Ok(Self {
+        Ok(Self::with_client(LmoClient::new()?, sse_url, download_id))
+    }
+
+    /// Create a download progress stream sharing an existing client's connection pool and configuration
+    pub fn with_client(client: LmoClient, sse_url: String, download_id: DownloadId) -> Self {
+        Self {
+            client,
             sse_url,
             download_id,
-        })
+        }
     }
 
     /// Get the download ID
@@ -51,78 +106,108 @@ impl DownloadProgressStream {
 
     /// Convert to a stream of download events using a basic SSE implementation
     pub fn into_stream(self) -> impl Stream<Item = ClientResult<DownloadEvent>> {
-        let sse_url = self.sse_url.clone();
+        self.into_raw_stream().map(|raw| {
+            raw.and_then(|json_data| {
+                serde_json::from_str::<DownloadEvent>(&json_data).map_err(|e| {
+                    error!("Failed to parse download event JSON: {}", e);
+                    ClientError::JsonParseError(e)
+                })
+            })
+        })
+    }
+
+    /// Convert to a stream of download events with per-file progress
+    ///
+    /// Servers that do not report per-file progress yield events with no files.
+    pub fn into_detailed_stream(self) -> impl Stream<Item = ClientResult<DetailedDownloadEvent>> {
+        self.into_raw_stream().map(|raw| {
+            raw.and_then(|json_data| {
+                DetailedDownloadEvent::from_json(&json_data).map_err(|e| {
+                    error!("Failed to parse download event JSON: {}", e);
+                    ClientError::JsonParseError(e)
+                })
+            })
+        })
+    }
+
+    /// Publish the latest download progress to a watch channel
+    ///
+    /// Waits for the first event, then updates the channel from a background
+    /// task until the download ends or every receiver is dropped.
+    pub async fn into_watch(self) -> ClientResult<watch::Receiver<DownloadProgress>> {
+        let download_id = self.download_id.clone();
+        let mut events = Box::pin(self.into_stream().until_terminal());
+        
+        let first = match events.next().await {
+            Some(event) => event?,
+            None => {
+                return Err(ClientError::StreamError(format!(
+                    "Download {} progress stream ended before any event",
+                    download_id
+                )))
+            }
+        };
+        
+        let (sender, receiver) = watch::channel(first.progress);
         
+        tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = sender.closed() => {
+                        debug!("All progress watchers for download {} dropped", download_id);
+                        break;
+                    }
+                    event = events.next() => event,
+                };
+                
+                match event {
+                    Some(Ok(event)) => {
+                        let _ = sender.send(event.progress);
+                    }
+                    Some(Err(e)) => warn!("Download {} progress error: {}", download_id, e),
+                    None => break,
+                }
+            }
+        });
+        
+        Ok(receiver)
+    }
+
+    /// Stream the JSON payloads of download events, skipping keep-alives
+    fn into_raw_stream(self) -> impl Stream<Item = ClientResult<String>> {
         async_stream::stream! {
-            // Create HTTP client for SSE with timeout
-            let client = reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(120)) // 2 minute timeout
-                .build()
-                .map_err(|e| ClientError::ConfigError(format!("Failed to create HTTP client: {}", e)))?;
-            
-            // Make SSE request
-            let response = match client
-                .get(&sse_url)
-                .header("Accept", "text/event-stream")
-                .header("Cache-Control", "no-cache")
-                .send()
+            // Make SSE request through the shared client (auth, headers, idle timeout);
+            // there is no overall deadline since a download takes as long as it takes
+            let response = match self.client
+                .make_request_stream(reqwest::Method::GET, &self.sse_url, None::<&()>, None)
                 .await
             {
                 Ok(resp) => resp,
                 Err(e) => {
-                    yield Err(ClientError::HttpError(e));
+                    yield Err(e);
                     return;
                 }
             };
+            let format = StreamFormat::from_content_type(
+                response.headers().get(CONTENT_TYPE).and_then(|value| value.to_str().ok()),
+            );
             
             // Stream the response bytes
             let mut bytes_stream = response.bytes_stream();
-            let mut buffer = String::new();
+            let mut parser = StreamParser::new(format);
             
             while let Some(chunk_result) = bytes_stream.next().await {
                 match chunk_result {
                     Ok(chunk) => {
-                        // Convert bytes to string and add to buffer
-                        let chunk_str = match String::from_utf8(chunk.to_vec()) {
-                            Ok(s) => s,
-                            Err(e) => {
-                                error!("Invalid UTF-8 in SSE stream: {}", e);
-                                continue;
-                            }
-                        };
-                        
-                        buffer.push_str(&chunk_str);
-                        
-                        // Process complete SSE events (ending with \n\n)
-                        while let Some(event_end) = buffer.find("\n\n") {
-                            let event_data = buffer[..event_end].to_string();
-                            buffer.drain(..event_end + 2);
-                            
-                            debug!("Raw SSE event data: {:?}", event_data);
-                            
-                            // Parse SSE event
-                            if let Some(parsed_event) = Self::parse_sse_event(&event_data) {
-                                match parsed_event {
-                                    ParsedSseEvent::DownloadEvent(json_data) => {
-                                        match serde_json::from_str::<DownloadEvent>(&json_data) {
-                                            Ok(download_event) => yield Ok(download_event),
-                                            Err(e) => {
-                                                error!("Failed to parse download event JSON: {}", e);
-                                                yield Err(ClientError::JsonParseError(e));
-                                            }
-                                        }
-                                    }
-                                    ParsedSseEvent::KeepAlive => {
-                                        // Keep-alive event received, don't yield anything but continue the stream
-                                        debug!("Received keep-alive event");
-                                    }
-                                    ParsedSseEvent::Heartbeat => {
-                                        // Heartbeat event received, don't yield anything but continue the stream
-                                        debug!("Received heartbeat event");
-                                    }
+                        for frame in parser.push(&chunk) {
+                            match frame {
+                                StreamFrame::Data(json_data) if json_data.starts_with('{') => {
+                                    yield Ok(json_data);
                                 }
-                            } else {
-                                debug!("Failed to parse SSE event: {:?}", event_data);
+                                StreamFrame::KeepAlive => {
+                                    // Keep-alive or heartbeat received, don't yield anything but continue the stream
+                                }
+                                frame => debug!("Ignoring SSE frame: {:?}", frame),
                             }
                         }
                     }
@@ -143,57 +228,284 @@ impl DownloadProgressStream {
             }
         }
     }
-    
-    /// Parse SSE event format and extract structured data
-    fn parse_sse_event(event_data: &str) -> Option<ParsedSseEvent> {
-        let mut event_type = None;
-        let mut data = None;
-        let mut has_comment = false;
-        
-        for line in event_data.lines() {
-            if let Some(event) = line.strip_prefix("event: ") {
-                event_type = Some(event.to_string());
-            } else if let Some(event_data) = line.strip_prefix("data: ") {
-                data = Some(event_data.to_string());
-            } else if line.starts_with(":") {
-                // SSE comment line (used for keep-alive)
-                has_comment = true;
+}
+
+/// Whether a download event ends the download (completed, failed or cancelled)
+pub fn is_terminal_event(event: &DownloadEvent) -> bool {
+    matches!(
+        event.event_type,
+        DownloadEventType::Completed | DownloadEventType::Failed | DownloadEventType::Cancelled
+    )
+}
+
+/// Result of a batch download, decided by its last event
+///
+/// Only a `Completed` event is a success. A stream that ends without a
+/// terminal event fails with its last error, or a stream error if it had none.
+fn download_outcome(
+    download_id: &str,
+    last_event_type: Option<&DownloadEventType>,
+    message: Option<&str>,
+    last_error: Option<ClientError>,
+) -> ClientResult<()> {
+    let details = message.map(|message| format!(": {}", message)).unwrap_or_default();
+    match last_event_type {
+        Some(DownloadEventType::Completed) => Ok(()),
+        Some(DownloadEventType::Failed) => Err(ClientError::ModelOperationError(format!(
+            "Download {} failed{}",
+            download_id, details
+        ))),
+        Some(DownloadEventType::Cancelled) => Err(ClientError::Cancelled(format!(
+            "Download {} was cancelled{}",
+            download_id, details
+        ))),
+        _ => Err(last_error.unwrap_or_else(|| {
+            ClientError::StreamError(format!(
+                "Download {} progress stream ended before the download finished",
+                download_id
+            ))
+        })),
+    }
+}
+
+/// Whether a progress stream that ended after an event of `last_event_type` should be reopened
+///
+/// True when the download has not finished and the stream was cut off by the
+/// connection rather than rejected by the server.
+fn should_reconnect(last_event_type: Option<&DownloadEventType>, last_error: Option<&ClientError>) -> bool {
+    if matches!(
+        last_event_type,
+        Some(DownloadEventType::Completed | DownloadEventType::Failed | DownloadEventType::Cancelled)
+    ) {
+        return false;
+    }
+    match last_error {
+        None => true,
+        Some(ClientError::HttpError(_) | ClientError::StreamError(_)) => true,
+        Some(error) => error.is_retryable(),
+    }
+}
+
+/// Combinators for streams of download events
+///
+/// Errors are always passed through unchanged.
+pub trait DownloadEventStreamExt: Stream<Item = ClientResult<DownloadEvent>> + Sized {
+    /// Keep only progress events
+    fn progress_only(self) -> impl Stream<Item = ClientResult<DownloadEvent>> {
+        self.filter(|event| match event {
+            Ok(event) => matches!(event.event_type, DownloadEventType::Progress),
+            Err(_) => true,
+        })
+    }
+
+    /// Pass at most one progress event per `interval`; other events are never dropped
+    fn throttle(self, interval: Duration) -> impl Stream<Item = ClientResult<DownloadEvent>> {
+        async_stream::stream! {
+            let mut events = Box::pin(self);
+            let mut last_progress: Option<Instant> = None;
+            
+            while let Some(event) = events.next().await {
+                if let Ok(ref event) = event {
+                    if matches!(event.event_type, DownloadEventType::Progress) {
+                        let now = Instant::now();
+                        if last_progress.is_some_and(|last| now.duration_since(last) < interval) {
+                            continue;
+                        }
+                        last_progress = Some(now);
+                    }
+                }
+                yield event;
             }
         }
-        
-        match (event_type.as_deref(), data.as_deref()) {
-            (Some("heartbeat"), Some("ping")) => {
-                debug!("Parsed heartbeat event");
-                Some(ParsedSseEvent::Heartbeat)
-            }
-            (None, Some("keep-alive")) => {
-                debug!("Parsed keep-alive event");
-                Some(ParsedSseEvent::KeepAlive)
-            }
-            (_, Some(json_data)) if json_data.starts_with('{') && json_data.ends_with('}') => {
-                debug!("Parsed download event");
-                Some(ParsedSseEvent::DownloadEvent(json_data.to_string()))
-            }
-            // Handle empty data (keep-alive) or comment-only events
-            (None, Some("")) | (None, None) if has_comment => {
-                debug!("Parsed SSE comment/keep-alive event");
-                Some(ParsedSseEvent::KeepAlive)
-            }
-            _ => {
-                debug!("Unknown SSE event: event_type={:?}, data={:?}, has_comment={}", event_type, data, has_comment);
-                None // Unknown or invalid event
+    }
+
+    /// End the stream after the first terminal event, which is still yielded
+    fn until_terminal(self) -> impl Stream<Item = ClientResult<DownloadEvent>> {
+        async_stream::stream! {
+            let mut events = Box::pin(self);
+            
+            while let Some(event) = events.next().await {
+                let terminal = event.as_ref().is_ok_and(is_terminal_event);
+                yield event;
+                if terminal {
+                    break;
+                }
             }
         }
     }
 }
 
+impl<S> DownloadEventStreamExt for S where S: Stream<Item = ClientResult<DownloadEvent>> {}
+
+/// Event from a batch of concurrent downloads started with `download_many`
+#[derive(Debug)]
+pub enum BatchDownloadEvent {
+    /// The server accepted a download
+    Started {
+        model_name: String,
+        download_id: DownloadId,
+    },
+    /// Progress event for one download
+    Progress {
+        download_id: DownloadId,
+        event: DownloadEvent,
+    },
+    /// A download failed to start or its progress stream ended
+    Finished(DownloadResult),
+}
+
+/// Final outcome of one download in a batch
+#[derive(Debug)]
+pub struct DownloadResult {
+    /// Requested model name
+    pub model_name: String,
+    /// Download ID, if the server accepted the download
+    pub download_id: Option<DownloadId>,
+    /// Last progress event received before the stream ended
+    pub last_event: Option<DownloadEvent>,
+    /// Ok if the download completed, otherwise why it failed, was cancelled or lost its stream
+    pub result: ClientResult<()>,
+}
+
 impl LmoClient {
+    /// Start several downloads, running at most `max_parallel` at once
+    ///
+    /// Progress from all downloads is merged into one stream keyed by download ID.
+    /// Each download ends with a `BatchDownloadEvent::Finished` carrying its result.
+    pub fn download_many(
+        &self,
+        requests: Vec<DownloadModelRequest>,
+        max_parallel: usize,
+    ) -> impl Stream<Item = BatchDownloadEvent> {
+        info!("Starting {} downloads (max {} parallel)", requests.len(), max_parallel);
+        
+        let client = self.clone();
+        let downloads = futures::stream::iter(requests)
+            .map(move |request| Box::pin(Self::batch_download_events(client.clone(), request)));
+        
+        futures::StreamExt::flatten_unordered(downloads, max_parallel.max(1))
+    }
+
+    /// Start several downloads and wait for all of them, returning per-model results
+    pub async fn download_many_results(
+        &self,
+        requests: Vec<DownloadModelRequest>,
+        max_parallel: usize,
+    ) -> Vec<DownloadResult> {
+        let mut events = Box::pin(self.download_many(requests, max_parallel));
+        let mut results = Vec::new();
+        
+        while let Some(event) = events.next().await {
+            if let BatchDownloadEvent::Finished(result) = event {
+                results.push(result);
+            }
+        }
+        
+        results
+    }
+
+    /// Drive a single download of a batch from start to the end of its progress stream
+    fn batch_download_events(
+        client: LmoClient,
+        request: DownloadModelRequest,
+    ) -> impl Stream<Item = BatchDownloadEvent> {
+        async_stream::stream! {
+            let model_name = request.model_name.clone();
+            
+            let started = match client.download_start(request).await {
+                Ok(started) => started,
+                Err(e) => {
+                    yield BatchDownloadEvent::Finished(DownloadResult {
+                        model_name,
+                        download_id: None,
+                        last_event: None,
+                        result: Err(e),
+                    });
+                    return;
+                }
+            };
+            
+            let download_id = started.download_id;
+            yield BatchDownloadEvent::Started {
+                model_name: model_name.clone(),
+                download_id: download_id.clone(),
+            };
+            
+            let mut last_event: Option<DownloadEvent> = None;
+            let mut last_error = None;
+            let mut reconnects = 0;
+            
+            loop {
+                let progress = match client.download_progress_stream(&download_id).await {
+                    Ok(progress) => progress,
+                    Err(e) => {
+                        last_error = Some(e);
+                        break;
+                    }
+                };
+                
+                let mut events = Box::pin(progress.into_stream().until_terminal());
+                while let Some(event) = events.next().await {
+                    match event {
+                        Ok(event) => {
+                            reconnects = 0;
+                            last_error = None;
+                            last_event = Some(event.clone());
+                            yield BatchDownloadEvent::Progress {
+                                download_id: download_id.clone(),
+                                event,
+                            };
+                        }
+                        Err(e) => {
+                            warn!("Download {} progress error: {}", download_id, e);
+                            last_error = Some(e);
+                        }
+                    }
+                }
+                
+                // The server keeps downloading when the progress stream drops,
+                // so reconnect instead of reporting the download as failed
+                if !should_reconnect(last_event.as_ref().map(|event| &event.event_type), last_error.as_ref())
+                    || reconnects >= client.config().max_retries
+                {
+                    break;
+                }
+                reconnects += 1;
+                warn!(
+                    "Download {} progress stream ended early; reconnecting (attempt {})",
+                    download_id, reconnects
+                );
+                tokio::time::sleep(client.config().retry_delay).await;
+            }
+            
+            let result = download_outcome(
+                &download_id,
+                last_event.as_ref().map(|event| &event.event_type),
+                last_event.as_ref().and_then(|event| event.message.as_deref()),
+                last_error,
+            );
+            yield BatchDownloadEvent::Finished(DownloadResult {
+                model_name,
+                download_id: Some(download_id),
+                last_event,
+                result,
+            });
+        }
+    }
+
     /// Start a download and return a download ID immediately (new async API)
     pub async fn download_start(&self, request: DownloadModelRequest) -> ClientResult<StartDownloadResponse> {
+        self.download_start_with_meta(request).await.map(WithMeta::into_body)
+    }
+
+    /// Start a download and return its download ID, with response metadata
+    pub async fn download_start_with_meta(&self, request: DownloadModelRequest) -> ClientResult<WithMeta<StartDownloadResponse>> {
         info!("Starting async download for model: {}", request.model_name);
         
         let url = self.config().api_url(Endpoints::MODELS_DOWNLOAD)?;
-        let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await;
+        let response = self.with_model_suggestions(&request.model_name, response).await?;
+        let meta = ResponseMeta::from_response(&response);
         
         let download_response: StartDownloadResponse = response.json().await?;
         
@@ -203,7 +515,7 @@ impl LmoClient {
             download_response.download_id
         );
         
-        Ok(download_response)
+        Ok(WithMeta { body: download_response, meta })
     }
 
     /// Get a progress stream for a download using Server-Sent Events
@@ -213,7 +525,7 @@ impl LmoClient {
         
         debug!("Creating SSE stream for download {} at {}", download_id, sse_url);
         
-        DownloadProgressStream::new(sse_url, download_id.clone())
+        Ok(DownloadProgressStream::with_client(self.clone(), sse_url, download_id.clone()))
     }
 
     /// Control a download (pause, resume, cancel)
@@ -222,6 +534,15 @@ impl LmoClient {
         download_id: &DownloadId,
         action: &str,
     ) -> ClientResult<DownloadControlResponse> {
+        self.download_control_with_meta(download_id, action).await.map(WithMeta::into_body)
+    }
+
+    /// Control a download (pause, resume, cancel), with response metadata
+    pub async fn download_control_with_meta(
+        &self,
+        download_id: &DownloadId,
+        action: &str,
+    ) -> ClientResult<WithMeta<DownloadControlResponse>> {
         info!("Controlling download {}: {}", download_id, action);
         
         let control_endpoint = Endpoints::download_control(download_id);
@@ -232,6 +553,8 @@ impl LmoClient {
         };
         
         let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let meta = ResponseMeta::from_response(&response);
+        
         let control_response: DownloadControlResponse = response.json().await?;
         
         if control_response.success {
@@ -248,7 +571,7 @@ impl LmoClient {
             );
         }
         
-        Ok(control_response)
+        Ok(WithMeta { body: control_response, meta })
     }
 
     /// Pause a download
@@ -267,13 +590,19 @@ impl LmoClient {
     }
 
     /// Legacy synchronous download (uses the /download/legacy endpoint)
-    pub async fn download_model_legacy(&self, request: DownloadModelRequest) -> ClientResult<crate::models::DownloadModelResponse> {
+    pub async fn download_model_legacy(&self, request: DownloadModelRequest) -> ClientResult<DownloadModelResponse> {
+        self.download_model_legacy_with_meta(request).await.map(WithMeta::into_body)
+    }
+
+    /// Legacy synchronous download, with response metadata
+    pub async fn download_model_legacy_with_meta(&self, request: DownloadModelRequest) -> ClientResult<WithMeta<DownloadModelResponse>> {
         info!("Downloading model (legacy): {}", request.model_name);
         
         let url = self.config().api_url(Endpoints::MODELS_DOWNLOAD_LEGACY)?;
         let response = self.make_request(reqwest::Method::POST, url, Some(&request)).await?;
+        let meta = ResponseMeta::from_response(&response);
         
-        let download_response: crate::models::DownloadModelResponse = response.json().await?;
+        let download_response: DownloadModelResponse = response.json().await?;
         
         if download_response.success {
             let size_mb = download_response.size_bytes
@@ -292,8 +621,9 @@ impl LmoClient {
                 download_response.message);
         }
         
-        Ok(download_response)
+        Ok(WithMeta { body: download_response, meta })
     }
+
 }
 
 #[cfg(test)]
@@ -311,4 +641,90 @@ mod tests {
         let stream = stream.unwrap();
         assert_eq!(stream.download_id(), "test-123");
     }
+
+    #[test]
+    fn test_file_progress_fraction() {
+        let file = FileProgress {
+            filename: "model-00001-of-00002.safetensors".to_string(),
+            downloaded_bytes: 250,
+            total_bytes: Some(1000),
+            completed: false,
+        };
+        assert_eq!(file.fraction(), Some(0.25));
+
+        let file = FileProgress { total_bytes: None, ..file };
+        assert_eq!(file.fraction(), None);
+    }
+
+    #[test]
+    fn test_file_progress_fields_default_to_empty() {
+        let fields: FileProgressFields = serde_json::from_str(r#"{"download_id":"abc"}"#).unwrap();
+        assert!(fields.files.is_empty());
+
+        let fields: FileProgressFields = serde_json::from_str(
+            r#"{"files":[{"filename":"tokenizer.json","downloaded_bytes":10,"total_bytes":10,"completed":true}]}"#,
+        )
+        .unwrap();
+        assert_eq!(fields.files.len(), 1);
+        assert_eq!(fields.files[0].fraction(), Some(1.0));
+    }
+
+    #[test]
+    fn test_download_outcome() {
+        assert!(download_outcome("d1", Some(&DownloadEventType::Completed), None, None).is_ok());
+        assert!(matches!(
+            download_outcome("d1", Some(&DownloadEventType::Failed), Some("disk full"), None),
+            Err(ClientError::ModelOperationError(message)) if message == "Download d1 failed: disk full"
+        ));
+        assert!(matches!(
+            download_outcome("d1", Some(&DownloadEventType::Cancelled), None, None),
+            Err(ClientError::Cancelled(_))
+        ));
+        assert!(matches!(
+            download_outcome("d1", Some(&DownloadEventType::Progress), None, None),
+            Err(ClientError::StreamError(_))
+        ));
+        assert!(matches!(
+            download_outcome("d1", None, None, Some(ClientError::TimeoutError("idle".to_string()))),
+            Err(ClientError::TimeoutError(_))
+        ));
+    }
+
+    #[test]
+    fn test_should_reconnect() {
+        assert!(should_reconnect(None, None));
+        assert!(should_reconnect(Some(&DownloadEventType::Progress), None));
+        assert!(should_reconnect(
+            Some(&DownloadEventType::Progress),
+            Some(&ClientError::StreamError("connection reset".to_string()))
+        ));
+        assert!(!should_reconnect(Some(&DownloadEventType::Completed), None));
+        assert!(!should_reconnect(Some(&DownloadEventType::Failed), None));
+        assert!(!should_reconnect(None, Some(&ClientError::from_response(404, "Unknown download".to_string()))));
+    }
+
+    #[tokio::test]
+    async fn test_download_many_reports_start_failures() {
+        let config = crate::config::ClientConfig::new("http://127.0.0.1:9")
+            .unwrap()
+            .with_max_retries(0);
+        let client = LmoClient::with_config(config).unwrap();
+
+        let requests = ["model-a", "model-b"]
+            .iter()
+            .map(|name| DownloadModelRequest {
+                model_name: name.to_string(),
+                format_hint: None,
+                force_redownload: false,
+                custom_directory: None,
+            })
+            .collect();
+
+        let mut results = client.download_many_results(requests, 2).await;
+        results.sort_by(|a, b| a.model_name.cmp(&b.model_name));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].model_name, "model-a");
+        assert!(results.iter().all(|r| r.download_id.is_none() && r.result.is_err()));
+    }
 }
\ No newline at end of file
@@ -19,14 +19,35 @@ pub enum ClientError {
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     #[error("Authentication failed: {0}")]
     AuthenticationError(String),
 
     #[error("Server error: {status} - {message}")]
     ServerError { status: u16, message: String },
 
-    #[error("Model not found: {0}")]
-    ModelNotFound(String),
+    #[error("Rate limit exceeded: {0}")]
+    RateLimited(String),
+
+    #[error("Model not found: {}{}", not_found_subject(model, message), did_you_mean(suggestions))]
+    ModelNotFound {
+        model: String,
+        /// Error text from the server, empty for client-side lookups
+        message: String,
+        /// Known model IDs close to the requested one
+        suggestions: Vec<String>,
+    },
+
+    #[error("Context length exceeded: {message}")]
+    ContextLengthExceeded {
+        /// Tokens the request needed, if the server reported it
+        requested_tokens: Option<u32>,
+        /// Model context size, if the server reported it
+        context_limit: Option<u32>,
+        message: String,
+    },
 
     #[error("Model operation failed: {0}")]
     ModelOperationError(String),
@@ -40,6 +61,12 @@ pub enum ClientError {
     #[error("Invalid response format: {0}")]
     InvalidResponse(String),
 
+    #[error("Completion blocked by guardrail: {0}")]
+    GuardrailBlocked(String),
+
+    #[error("Request cancelled: {0}")]
+    Cancelled(String),
+
     #[error("Stream error: {0}")]
     StreamError(String),
 
@@ -48,6 +75,9 @@ pub enum ClientError {
 
     #[error("JSON parsing failed: {0}")]
     JsonParseError(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
 }
 
 impl ClientError {
@@ -55,16 +85,39 @@ impl ClientError {
     pub fn from_response(status: u16, message: String) -> Self {
         match status {
             401 | 403 => Self::AuthenticationError(message),
-            404 => Self::ModelNotFound(message),
+            404 => Self::ModelNotFound {
+                model: String::new(),
+                message,
+                suggestions: Vec::new(),
+            },
+            429 => Self::RateLimited(message),
+            400 | 413 | 422 if is_context_length_message(&message) => {
+                let (requested_tokens, context_limit) = parse_context_tokens(&message);
+                Self::ContextLengthExceeded {
+                    requested_tokens,
+                    context_limit,
+                    message,
+                }
+            }
             _ => Self::ServerError { status, message },
         }
     }
 
+    /// Create a model-not-found error without suggestions
+    pub fn model_not_found<S: Into<String>>(model: S) -> Self {
+        Self::ModelNotFound {
+            model: model.into(),
+            message: String::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
     /// Check if this error type is retryable
     pub fn is_retryable(&self) -> bool {
         match self {
             Self::HttpError(e) => e.is_timeout() || e.is_connect(),
             Self::ServerError { status, .. } => matches!(status, 500..=599),
+            Self::RateLimited(_) => true,
             Self::TimeoutError(_) => true,
             Self::NetworkError(_) => true,
             _ => false,
@@ -75,12 +128,119 @@ impl ClientError {
     pub fn status_code(&self) -> Option<u16> {
         match self {
             Self::ServerError { status, .. } => Some(*status),
+            Self::RateLimited(_) => Some(429),
             Self::HttpError(e) => e.status().map(|s| s.as_u16()),
             _ => None,
         }
     }
+
+    /// Copy this error for another caller sharing the same request
+    ///
+    /// Variants wrapping non-cloneable library errors are mapped to the
+    /// closest equivalent carrying the same message.
+    pub(crate) fn duplicate(&self) -> Self {
+        match self {
+            Self::HttpError(e) if e.is_timeout() => Self::TimeoutError(e.to_string()),
+            Self::HttpError(e) => match e.status() {
+                Some(status) => Self::from_response(status.as_u16(), e.to_string()),
+                None if e.is_connect() || e.is_request() => Self::NetworkError(e.to_string()),
+                None if e.is_builder() => Self::ConfigError(e.to_string()),
+                None => Self::InvalidResponse(e.to_string()),
+            },
+            Self::ConfigError(msg) => Self::ConfigError(msg.clone()),
+            Self::InvalidArgument(msg) => Self::InvalidArgument(msg.clone()),
+            Self::AuthenticationError(msg) => Self::AuthenticationError(msg.clone()),
+            Self::ServerError { status, message } => Self::ServerError {
+                status: *status,
+                message: message.clone(),
+            },
+            Self::RateLimited(msg) => Self::RateLimited(msg.clone()),
+            Self::ModelNotFound { model, message, suggestions } => Self::ModelNotFound {
+                model: model.clone(),
+                message: message.clone(),
+                suggestions: suggestions.clone(),
+            },
+            Self::ContextLengthExceeded { requested_tokens, context_limit, message } => Self::ContextLengthExceeded {
+                requested_tokens: *requested_tokens,
+                context_limit: *context_limit,
+                message: message.clone(),
+            },
+            Self::ModelOperationError(msg) => Self::ModelOperationError(msg.clone()),
+            Self::TimeoutError(msg) => Self::TimeoutError(msg.clone()),
+            Self::NetworkError(msg) => Self::NetworkError(msg.clone()),
+            Self::InvalidResponse(msg) => Self::InvalidResponse(msg.clone()),
+            Self::GuardrailBlocked(msg) => Self::GuardrailBlocked(msg.clone()),
+            Self::Cancelled(msg) => Self::Cancelled(msg.clone()),
+            Self::StreamError(msg) => Self::StreamError(msg.clone()),
+            Self::StreamEvent(msg) => Self::StreamEvent(msg.clone()),
+            Self::JsonParseError(e) => Self::InvalidResponse(e.to_string()),
+            Self::IoError(e) => Self::IoError(std::io::Error::new(e.kind(), e.to_string())),
+        }
+    }
+}
+
+/// Name the missing model, keeping the server's explanation when it adds anything
+fn not_found_subject(model: &str, message: &str) -> String {
+    match (model.is_empty(), message.is_empty() || message == model) {
+        (true, _) => message.to_string(),
+        (false, true) => model.to_string(),
+        (false, false) => format!("{} ({})", model, message),
+    }
 }
 
+/// Render suggestions as a " (did you mean ...?)" suffix
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(" (did you mean {}?)", suggestions.join(", "))
+    }
+}
+
+/// Whether a server error message reports an over-long prompt
+fn is_context_length_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    ["context length", "context size", "context window", "maximum context"]
+        .iter()
+        .any(|phrase| message.contains(phrase))
+        && (message.contains("exceed") || message.contains("maximum") || message.contains("too long"))
+}
+
+/// Extract `(requested, limit)` token counts from a context-length message
+///
+/// Each "N tokens" is attributed to the context limit when the text leading
+/// up to it mentions the context or a maximum, otherwise to the request.
+/// Covers both "maximum context length is 4096 tokens. However, you requested
+/// 5000 tokens" and "request (5000 tokens) exceeds the available context size (4096 tokens)".
+fn parse_context_tokens(message: &str) -> (Option<u32>, Option<u32>) {
+    let lower = message.to_lowercase();
+    let words: Vec<&str> = lower.split_whitespace().collect();
+    let mut requested = None;
+    let mut limit = None;
+    let mut lead = String::new();
+
+    for (i, word) in words.iter().enumerate() {
+        let count = word.trim_matches(|c: char| !c.is_ascii_digit()).parse::<u32>().ok();
+        let is_token_count = words.get(i + 1).is_some_and(|next| next.starts_with("token"));
+
+        match count {
+            Some(count) if is_token_count => {
+                if lead.contains("context") || lead.contains("maximum") {
+                    limit = limit.or(Some(count));
+                } else {
+                    requested = requested.or(Some(count));
+                }
+                lead.clear();
+            }
+            _ => {
+                lead.push_str(word);
+                lead.push(' ');
+            }
+        }
+    }
+
+    (requested, limit)
+}
 
 impl From<url::ParseError> for ClientError {
     fn from(err: url::ParseError) -> Self {
@@ -94,9 +254,17 @@ mod tests {
 
     #[test]
     fn test_error_types() {
-        let error = ClientError::ModelNotFound("test-model".to_string());
+        let error = ClientError::model_not_found("test-model");
         assert!(!error.is_retryable());
         assert_eq!(error.status_code(), None);
+        assert_eq!(error.to_string(), "Model not found: test-model");
+
+        let error = ClientError::ModelNotFound {
+            model: "llama3".to_string(),
+            message: String::new(),
+            suggestions: vec!["llama-3".to_string(), "llama-3.1".to_string()],
+        };
+        assert_eq!(error.to_string(), "Model not found: llama3 (did you mean llama-3, llama-3.1?)");
 
         let error = ClientError::ServerError {
             status: 500,
@@ -108,13 +276,78 @@ mod tests {
 
     #[test]
     fn test_from_response() {
-        let error = ClientError::from_response(404, "Not found".to_string());
-        assert!(matches!(error, ClientError::ModelNotFound(_)));
+        let error = ClientError::from_response(404, "No route for /v1/foo".to_string());
+        assert!(matches!(error, ClientError::ModelNotFound { .. }));
+        assert_eq!(error.to_string(), "Model not found: No route for /v1/foo");
 
         let error = ClientError::from_response(401, "Unauthorized".to_string());
         assert!(matches!(error, ClientError::AuthenticationError(_)));
 
+        let error = ClientError::from_response(429, "Too many requests".to_string());
+        assert!(matches!(error, ClientError::RateLimited(_)));
+        assert!(error.is_retryable());
+        assert_eq!(error.status_code(), Some(429));
+
         let error = ClientError::from_response(500, "Server error".to_string());
         assert!(matches!(error, ClientError::ServerError { .. }));
     }
+
+    #[tokio::test]
+    async fn test_duplicate_http_errors() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let error = ClientError::HttpError(reqwest::get("http://127.0.0.1:9").await.unwrap_err());
+        assert!(matches!(error.duplicate(), ClientError::NetworkError(_)));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 8\r\n\r\nnot json")
+                .await
+                .unwrap();
+        });
+
+        let response = reqwest::get(url).await.unwrap();
+        let error = ClientError::HttpError(response.json::<serde_json::Value>().await.unwrap_err());
+        let duplicate = error.duplicate();
+        assert!(matches!(duplicate, ClientError::InvalidResponse(_)));
+        assert!(!duplicate.is_retryable());
+    }
+
+    #[test]
+    fn test_context_length_errors() {
+        let error = ClientError::from_response(
+            400,
+            "This model's maximum context length is 4096 tokens. However, you requested 5000 tokens \
+             (4000 in the messages, 1000 in the completion)."
+                .to_string(),
+        );
+        assert!(matches!(
+            error,
+            ClientError::ContextLengthExceeded { requested_tokens: Some(5000), context_limit: Some(4096), .. }
+        ));
+        assert!(!error.is_retryable());
+
+        let error = ClientError::from_response(
+            400,
+            "the request (8192 tokens) exceeds the available context size (4096 tokens)".to_string(),
+        );
+        assert!(matches!(
+            error,
+            ClientError::ContextLengthExceeded { requested_tokens: Some(8192), context_limit: Some(4096), .. }
+        ));
+
+        let error = ClientError::from_response(400, "Prompt exceeds the context window".to_string());
+        assert!(matches!(
+            error,
+            ClientError::ContextLengthExceeded { requested_tokens: None, context_limit: None, .. }
+        ));
+
+        let error = ClientError::from_response(400, "Invalid temperature".to_string());
+        assert!(matches!(error, ClientError::ServerError { status: 400, .. }));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,224 @@
+/*!
+ * Guardrails
+ *
+ * User-supplied validators run over every completion before it is returned,
+ * blocking or redacting output that violates a policy (PII, profanity, ...).
+ */
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::error::{ClientError, ClientResult};
+
+/// Result of checking a completion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuardrailVerdict {
+    /// The text is acceptable as is
+    Pass,
+    /// The text violates the guardrail
+    Violation {
+        /// Why the text was flagged
+        reason: String,
+        /// Text with the offending parts removed, used by `GuardrailPolicy::Redact`
+        redacted: String,
+    },
+}
+
+/// What to do when a guardrail reports a violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardrailPolicy {
+    /// Fail the request with `ClientError::GuardrailBlocked`
+    Block,
+    /// Return the redacted text instead
+    Redact,
+}
+
+/// Validator run over completion text
+pub trait Guardrail: Send + Sync {
+    fn check(&self, text: &str) -> GuardrailVerdict;
+}
+
+impl<F> Guardrail for F
+where
+    F: Fn(&str) -> GuardrailVerdict + Send + Sync,
+{
+    fn check(&self, text: &str) -> GuardrailVerdict {
+        self(text)
+    }
+}
+
+/// Registered guardrails, applied in order
+#[derive(Clone, Default)]
+pub(crate) struct Guardrails(Vec<(Arc<dyn Guardrail>, GuardrailPolicy)>);
+
+impl Guardrails {
+    pub fn push(&mut self, guardrail: Arc<dyn Guardrail>, policy: GuardrailPolicy) {
+        self.0.push((guardrail, policy));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Run the text through every guardrail, returning the possibly redacted text
+    pub fn apply(&self, mut text: String) -> ClientResult<String> {
+        for (guardrail, policy) in &self.0 {
+            if let GuardrailVerdict::Violation { reason, redacted } = guardrail.check(&text) {
+                match policy {
+                    GuardrailPolicy::Block => return Err(ClientError::GuardrailBlocked(reason)),
+                    GuardrailPolicy::Redact => {
+                        warn!("Guardrail redacted completion: {}", reason);
+                        text = redacted;
+                    }
+                }
+            }
+        }
+
+        Ok(text)
+    }
+}
+
+/// Streamed text held back from the caller, so a violation split across
+/// chunks can still be redacted before any of it is returned
+const STREAM_HOLD_BACK: usize = 64;
+
+/// Guardrail state for one streamed completion
+///
+/// Each delta is checked together with the held-back tail of the text before
+/// it, so a violation up to `STREAM_HOLD_BACK` bytes long is caught even when
+/// split across chunks, while each chunk costs only its own length to check.
+/// The tail is only released once more text arrives or the choice finishes.
+#[derive(Debug, Default)]
+pub(crate) struct StreamGuard {
+    guardrails: Guardrails,
+    /// Checked text not yet released, per choice
+    pending: BTreeMap<u32, String>,
+}
+
+impl StreamGuard {
+    pub fn new(guardrails: Guardrails) -> Self {
+        Self {
+            guardrails,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.guardrails.is_empty()
+    }
+
+    /// Add a content delta for a choice, returning the checked text now safe to release
+    pub fn push(&mut self, index: u32, delta: &str) -> ClientResult<String> {
+        let pending = self.pending.entry(index).or_default();
+        pending.push_str(delta);
+
+        let mut checked = self.guardrails.apply(std::mem::take(pending))?;
+        let end = floor_char_boundary(&checked, checked.len().saturating_sub(STREAM_HOLD_BACK));
+        *pending = checked.split_off(end);
+        Ok(checked)
+    }
+
+    /// Release all remaining checked text of a finished choice
+    pub fn finish(&mut self, index: u32) -> String {
+        self.pending.remove(&index).unwrap_or_default()
+    }
+
+    /// Release the remaining text of every choice, for a stream that ended without finish reasons
+    pub fn finish_all(&mut self) -> Vec<(u32, String)> {
+        std::mem::take(&mut self.pending)
+            .into_iter()
+            .filter(|(_, text)| !text.is_empty())
+            .collect()
+    }
+}
+
+/// Largest char boundary at or before `index`
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+impl fmt::Debug for Guardrails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Guardrails({})", self.0.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn no_secrets(text: &str) -> GuardrailVerdict {
+        if text.contains("secret") {
+            GuardrailVerdict::Violation {
+                reason: "mentions a secret".to_string(),
+                redacted: text.replace("secret", "[REDACTED]"),
+            }
+        } else {
+            GuardrailVerdict::Pass
+        }
+    }
+
+    #[test]
+    fn test_guardrail_policies() {
+        let mut guardrails = Guardrails::default();
+        guardrails.push(Arc::new(no_secrets), GuardrailPolicy::Redact);
+
+        assert_eq!(guardrails.apply("hello".to_string()).unwrap(), "hello");
+        assert_eq!(guardrails.apply("the secret is 42".to_string()).unwrap(), "the [REDACTED] is 42");
+
+        let mut guardrails = Guardrails::default();
+        guardrails.push(Arc::new(no_secrets), GuardrailPolicy::Block);
+        assert!(matches!(
+            guardrails.apply("the secret is 42".to_string()),
+            Err(ClientError::GuardrailBlocked(_))
+        ));
+    }
+
+    #[test]
+    fn test_stream_guard_checks_across_chunks() {
+        let mut guardrails = Guardrails::default();
+        guardrails.push(Arc::new(no_secrets), GuardrailPolicy::Redact);
+        let mut guard = StreamGuard::new(guardrails);
+
+        let mut released = guard.push(0, "the sec").unwrap();
+        released.push_str(&guard.push(0, "ret is 42").unwrap());
+        released.push_str(&guard.finish(0));
+        assert_eq!(released, "the [REDACTED] is 42");
+
+        let mut guardrails = Guardrails::default();
+        guardrails.push(Arc::new(no_secrets), GuardrailPolicy::Block);
+        let mut guard = StreamGuard::new(guardrails);
+        assert!(guard.push(1, "a sec").is_ok());
+        assert!(matches!(guard.push(1, "ret"), Err(ClientError::GuardrailBlocked(_))));
+    }
+
+    #[test]
+    fn test_stream_guard_checks_only_the_window() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = calls.clone();
+        let mut guardrails = Guardrails::default();
+        guardrails.push(
+            Arc::new(move |text: &str| {
+                seen.lock().unwrap().push(text.len());
+                no_secrets(text)
+            }),
+            GuardrailPolicy::Block,
+        );
+        let mut guard = StreamGuard::new(guardrails);
+
+        let long = "a".repeat(1000);
+        assert_eq!(guard.push(0, &long).unwrap().len(), 1000 - STREAM_HOLD_BACK);
+        for _ in 0..10 {
+            assert!(guard.push(0, &long).is_ok());
+        }
+        assert!(calls.lock().unwrap().iter().all(|&len| len <= 1000 + STREAM_HOLD_BACK));
+
+        assert!(guard.push(0, "the sec").is_ok());
+        assert!(matches!(guard.push(0, "ret is 42"), Err(ClientError::GuardrailBlocked(_))));
+    }
+}
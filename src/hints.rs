@@ -0,0 +1,95 @@
+/*!
+ * Error Hints
+ *
+ * Actionable suggestions for common failures, shared by every front end
+ * that reports a `ClientError` to a user. Hints name no commands; front
+ * ends add their own.
+ */
+
+use std::fmt;
+
+use crate::error::ClientError;
+
+/// Suggested next step for resolving an error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// What to try, in one sentence
+    pub message: String,
+}
+
+impl Hint {
+    fn new<S: Into<String>>(message: S) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl ClientError {
+    /// Suggestions for resolving this error, most useful first
+    pub fn hints(&self) -> Vec<Hint> {
+        match self {
+            Self::HttpError(e) if e.is_connect() => vec![Self::unreachable_hint()],
+            Self::HttpError(e) if e.is_timeout() => vec![Self::timeout_hint()],
+            Self::NetworkError(_) => vec![Self::unreachable_hint()],
+            Self::TimeoutError(_) => vec![Self::timeout_hint()],
+            Self::AuthenticationError(_) => vec![Hint::new("Check that the configured API key is valid for this server")],
+            Self::ModelNotFound { suggestions, .. } => {
+                let list = Hint::new("List the available models to check the ID");
+                match suggestions.first() {
+                    Some(closest) => vec![Hint::new(format!("Did you mean {}?", closest)), list],
+                    None => vec![list],
+                }
+            }
+            Self::RateLimited(_) => vec![Hint::new("Wait before retrying, or lower the request rate")],
+            Self::ContextLengthExceeded { .. } => {
+                vec![Hint::new("Shorten the prompt, lower max_tokens, or set a truncation policy on the conversation")]
+            }
+            Self::ServerError { status, .. } if *status >= 500 => {
+                vec![Hint::new("The server failed internally; check the lmoserver logs")]
+            }
+            Self::ConfigError(_) => vec![Hint::new("Check the server URL, timeouts and headers in the client configuration")],
+            Self::InvalidArgument(_) => vec![Hint::new("Check the values passed to the failing call")],
+            _ => Vec::new(),
+        }
+    }
+
+    fn unreachable_hint() -> Hint {
+        Hint::new("Is lmoserver running and reachable at the configured URL?")
+    }
+
+    fn timeout_hint() -> Hint {
+        Hint::new("The server may be busy loading a model; retry or raise the client timeout")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hints() {
+        let hints = ClientError::NetworkError("connection refused".to_string()).hints();
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].to_string(), "Is lmoserver running and reachable at the configured URL?");
+
+        assert!(ClientError::ServerError { status: 400, message: "bad".to_string() }.hints().is_empty());
+        assert_eq!(ClientError::model_not_found("gpt-5").hints().len(), 1);
+
+        let error = ClientError::ModelNotFound {
+            model: "llama3".to_string(),
+            message: String::new(),
+            suggestions: vec!["llama-3".to_string()],
+        };
+        assert_eq!(error.hints()[0].to_string(), "Did you mean llama-3?");
+
+        let hints = ClientError::InvalidArgument("No candidate outputs to judge".to_string()).hints();
+        assert_eq!(hints[0].to_string(), "Check the values passed to the failing call");
+    }
+}
@@ -0,0 +1,153 @@
+/*!
+ * Incremental JSON Array Parsing
+ * 
+ * Splits a top-level JSON array into its elements as bytes arrive, so
+ * large list responses can be consumed before the body is complete.
+ */
+
+use crate::error::{ClientError, ClientResult};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArrayState {
+    /// Waiting for the opening bracket
+    Start,
+    /// Inside the top-level array
+    Elements,
+    /// Closing bracket seen
+    Done,
+}
+
+/// Incremental splitter for a top-level JSON array
+#[derive(Debug)]
+pub(crate) struct JsonArrayParser {
+    state: ArrayState,
+    /// Nesting depth within the current element (0 = between elements)
+    depth: usize,
+    in_string: bool,
+    escape: bool,
+    current: Vec<u8>,
+}
+
+impl JsonArrayParser {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: ArrayState::Start,
+            depth: 0,
+            in_string: false,
+            escape: false,
+            current: Vec::new(),
+        }
+    }
+
+    /// Feed a chunk of bytes, returning the raw JSON of every element completed by it
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> ClientResult<Vec<Vec<u8>>> {
+        let mut elements = Vec::new();
+
+        for &byte in chunk {
+            match self.state {
+                ArrayState::Start => {
+                    if byte == b'[' {
+                        self.state = ArrayState::Elements;
+                    } else if !byte.is_ascii_whitespace() {
+                        return Err(ClientError::InvalidResponse("Expected a JSON array".to_string()));
+                    }
+                }
+                ArrayState::Done => {
+                    if !byte.is_ascii_whitespace() {
+                        return Err(ClientError::InvalidResponse("Trailing data after JSON array".to_string()));
+                    }
+                }
+                ArrayState::Elements if self.in_string => {
+                    self.current.push(byte);
+                    if self.escape {
+                        self.escape = false;
+                    } else if byte == b'\\' {
+                        self.escape = true;
+                    } else if byte == b'"' {
+                        self.in_string = false;
+                    }
+                }
+                ArrayState::Elements => match byte {
+                    b'"' => {
+                        self.in_string = true;
+                        self.current.push(byte);
+                    }
+                    b'{' | b'[' => {
+                        self.depth += 1;
+                        self.current.push(byte);
+                    }
+                    b'}' | b']' if self.depth > 0 => {
+                        self.depth -= 1;
+                        self.current.push(byte);
+                        if self.depth == 0 {
+                            elements.push(std::mem::take(&mut self.current));
+                        }
+                    }
+                    b']' => {
+                        self.flush_scalar(&mut elements);
+                        self.state = ArrayState::Done;
+                    }
+                    b',' if self.depth == 0 => self.flush_scalar(&mut elements),
+                    _ if self.depth == 0 && byte.is_ascii_whitespace() => {}
+                    _ => self.current.push(byte),
+                },
+            }
+        }
+
+        Ok(elements)
+    }
+
+    /// Check that the array was closed once the input ends
+    pub(crate) fn finish(&self) -> ClientResult<()> {
+        match self.state {
+            ArrayState::Done => Ok(()),
+            _ => Err(ClientError::InvalidResponse("JSON array ended unexpectedly".to_string())),
+        }
+    }
+
+    /// Emit a pending scalar element (number, string, literal)
+    fn flush_scalar(&mut self, elements: &mut Vec<Vec<u8>>) {
+        if !self.current.is_empty() {
+            elements.push(std::mem::take(&mut self.current));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(chunks: &[&str]) -> Vec<String> {
+        let mut parser = JsonArrayParser::new();
+        let mut elements = Vec::new();
+        for chunk in chunks {
+            for element in parser.push(chunk.as_bytes()).unwrap() {
+                elements.push(String::from_utf8(element).unwrap());
+            }
+        }
+        parser.finish().unwrap();
+        elements
+    }
+
+    #[test]
+    fn test_split_across_chunks() {
+        let elements = split(&[r#" [{"id":"a","tags":["x]",{"#, r#""n":1}]}, {"id":"b\"}"}"#, "]\n"]);
+        assert_eq!(elements, vec![r#"{"id":"a","tags":["x]",{"n":1}]}"#, r#"{"id":"b\"}"}"#]);
+    }
+
+    #[test]
+    fn test_scalars_and_empty_array() {
+        assert_eq!(split(&["[1, \"two\",null]"]), vec!["1", "\"two\"", "null"]);
+        assert!(split(&["[", "]"]).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let mut parser = JsonArrayParser::new();
+        assert!(parser.push(b"{\"models\":[]}").is_err());
+
+        let mut parser = JsonArrayParser::new();
+        parser.push(b"[{\"id\":\"a\"}").unwrap();
+        assert!(parser.finish().is_err());
+    }
+}
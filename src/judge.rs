@@ -0,0 +1,166 @@
+/*!
+ * Model-as-Judge Scoring
+ *
+ * Grades candidate outputs against a rubric using a judge model, for
+ * evaluation and A/B comparison workflows.
+ */
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+
+use crate::client::LmoClient;
+use crate::error::{ClientError, ClientResult};
+use crate::models::ChatRequestBuilder;
+
+/// Highest score the judge may award
+pub const MAX_SCORE: f32 = 10.0;
+
+/// Score awarded to one candidate
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct JudgeScore {
+    /// Index of the candidate in the input slice
+    pub candidate: usize,
+    /// Score from 0 to `MAX_SCORE`
+    pub score: f32,
+    /// Judge's short justification
+    #[serde(default)]
+    pub rationale: Option<String>,
+}
+
+/// Scores for every candidate, in candidate order
+#[derive(Debug, Clone)]
+pub struct JudgeVerdict {
+    pub scores: Vec<JudgeScore>,
+    /// Raw judge output, kept for auditing
+    pub raw: String,
+}
+
+impl JudgeVerdict {
+    /// Index of the highest-scoring candidate (the first one on ties)
+    pub fn best(&self) -> Option<usize> {
+        self.scores
+            .iter()
+            .reduce(|best, s| if s.score > best.score { s } else { best })
+            .map(|s| s.candidate)
+    }
+}
+
+/// Structured reply requested from the judge
+#[derive(Debug, Deserialize)]
+struct JudgeReply {
+    scores: Vec<JudgeScore>,
+}
+
+impl LmoClient {
+    /// Score candidate outputs against a rubric with a judge model
+    pub async fn judge<S: AsRef<str>>(
+        &self,
+        candidate_outputs: &[S],
+        rubric: &str,
+        judge_model: &str,
+    ) -> ClientResult<JudgeVerdict> {
+        if candidate_outputs.is_empty() {
+            return Err(ClientError::InvalidArgument("No candidate outputs to judge".to_string()));
+        }
+
+        debug!("Judging {} candidates with {}", candidate_outputs.len(), judge_model);
+
+        let request = ChatRequestBuilder::new()
+            .model(judge_model)
+            .message("system", "You are a strict, impartial grader. Reply with JSON only.")
+            .message("user", judge_prompt(candidate_outputs, rubric).as_str())
+            .temperature(0.0)
+            .build();
+
+        let response = self.chat_completion(request).await?;
+        let raw = response
+            .choices
+            .first()
+            .map(|choice| choice.message.content.clone())
+            .ok_or_else(|| ClientError::InvalidResponse("Judge returned no choices".to_string()))?;
+
+        let scores = parse_scores(&raw, candidate_outputs.len())?;
+        info!("Judge {} scored {} candidates", judge_model, scores.len());
+
+        Ok(JudgeVerdict { scores, raw })
+    }
+}
+
+/// Format the grading prompt; candidates are numbered from 1 for the judge
+fn judge_prompt<S: AsRef<str>>(candidates: &[S], rubric: &str) -> String {
+    let mut prompt = format!(
+        "Grade each candidate answer against the rubric on a scale from 0 to {}.\n\nRubric:\n{}\n",
+        MAX_SCORE,
+        rubric.trim()
+    );
+
+    for (i, candidate) in candidates.iter().enumerate() {
+        prompt.push_str(&format!("\n<candidate {}>\n{}\n</candidate {}>\n", i + 1, candidate.as_ref().trim(), i + 1));
+    }
+
+    prompt.push_str(
+        "\nReply with a JSON object of the form \
+         {\"scores\": [{\"candidate\": 1, \"score\": 7.5, \"rationale\": \"...\"}]} \
+         containing one entry per candidate.",
+    );
+    prompt
+}
+
+/// Parse the judge's JSON reply, tolerating surrounding prose
+fn parse_scores(raw: &str, candidate_count: usize) -> ClientResult<Vec<JudgeScore>> {
+    let json = match (raw.find('{'), raw.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &raw[start..=end],
+        _ => return Err(ClientError::InvalidResponse(format!("Judge reply contains no JSON: {}", raw))),
+    };
+
+    let reply: JudgeReply = serde_json::from_str(json)?;
+    let mut scores = Vec::with_capacity(candidate_count);
+
+    for mut score in reply.scores {
+        // The judge numbers candidates from 1
+        if score.candidate == 0 || score.candidate > candidate_count {
+            return Err(ClientError::InvalidResponse(format!(
+                "Judge scored unknown candidate {}",
+                score.candidate
+            )));
+        }
+        score.candidate -= 1;
+        score.score = score.score.clamp(0.0, MAX_SCORE);
+        scores.push(score);
+    }
+
+    scores.sort_by_key(|s| s.candidate);
+    scores.dedup_by_key(|s| s.candidate);
+    if scores.len() != candidate_count {
+        return Err(ClientError::InvalidResponse(format!(
+            "Judge scored {} of {} candidates",
+            scores.len(),
+            candidate_count
+        )));
+    }
+
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scores() {
+        let raw = r#"Here are the grades:
+{"scores": [{"candidate": 2, "score": 9, "rationale": "Complete"}, {"candidate": 1, "score": 12}]}"#;
+
+        let scores = parse_scores(raw, 2).unwrap();
+        assert_eq!(scores[0].candidate, 0);
+        assert_eq!(scores[0].score, MAX_SCORE);
+        assert_eq!(scores[1].rationale.as_deref(), Some("Complete"));
+
+        let verdict = JudgeVerdict { scores, raw: raw.to_string() };
+        assert_eq!(verdict.best(), Some(0));
+
+        assert!(parse_scores(raw, 3).is_err());
+        assert!(parse_scores("no json here", 1).is_err());
+        assert!(judge_prompt(&["a", "b"], "Be correct").contains("<candidate 2>\nb\n"));
+    }
+}
@@ -4,20 +4,54 @@
  * HTTP client for communicating with the LMOxide server.
  */
 
+pub mod batch;
+pub mod catalog;
 pub mod client;
+mod coalesce;
 pub mod config;
+pub mod conversation;
 pub mod download;
 pub mod error;
+pub mod guardrail;
+pub mod hints;
+pub mod judge;
+mod json_stream;
+pub mod meta;
 pub mod models;
+pub mod queue;
+pub mod rate_limit;
+pub mod schedule;
+pub mod semantic_cache;
+mod stream_parser;
 pub mod streaming;
+pub mod structured;
+pub mod suggest;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 // Re-export main types for convenience
-pub use client::LmoClient;
+pub use client::{LmoClient, RequestHook};
 pub use config::{ClientConfig, ServerEndpoint};
+pub use conversation::{CompactSummary, Conversation, TruncationPolicy};
 pub use error::{ClientError, ClientResult};
+pub use guardrail::{Guardrail, GuardrailPolicy, GuardrailVerdict};
+pub use hints::Hint;
+pub use judge::{JudgeScore, JudgeVerdict};
+pub use meta::{ResponseMeta, WithMeta};
+pub use queue::RequestPriority;
+pub use rate_limit::RateLimitInfo;
+pub use schedule::ScheduledRequest;
+pub use semantic_cache::{CachedCompletion, Embedder, SemanticCache};
+pub use structured::JsonRepair;
 
 // Re-export model types
 pub use models::*;
 
+// Re-export catalog cache types
+pub use catalog::{CachedModelList, ModelCatalogCache};
+
 // Re-export download types
-pub use download::DownloadProgressStream;
\ No newline at end of file
+pub use download::{
+    BatchDownloadEvent, DetailedDownloadEvent, DownloadEventStreamExt, DownloadProgressStream, DownloadResult,
+    FileProgress,
+};
\ No newline at end of file
@@ -0,0 +1,99 @@
+/*!
+ * Response Metadata
+ * 
+ * Useful response headers (request ID, processing time, model revision)
+ * returned alongside typed bodies by the `*_with_meta` client methods.
+ */
+
+use reqwest::header::HeaderMap;
+use reqwest::Response;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::rate_limit::RateLimitInfo;
+
+/// Metadata taken from the headers of a server response
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ResponseMeta {
+    /// HTTP status code
+    pub status: u16,
+    /// Server-assigned request ID, for referencing a specific call
+    pub request_id: Option<String>,
+    /// Server-side processing time
+    pub processing_time: Option<Duration>,
+    /// Revision of the model that served the request
+    pub model_revision: Option<String>,
+    /// Rate-limit state reported with the response
+    pub rate_limit: Option<RateLimitInfo>,
+}
+
+impl ResponseMeta {
+    pub const REQUEST_ID: &'static str = "x-request-id";
+    pub const PROCESSING_TIME_MS: &'static str = "x-processing-time-ms";
+    pub const MODEL_REVISION: &'static str = "x-model-revision";
+
+    /// Extract metadata from a response
+    pub fn from_response(response: &Response) -> Self {
+        Self::from_headers(response.status().as_u16(), response.headers())
+    }
+
+    /// Extract metadata from a status code and response headers
+    pub fn from_headers(status: u16, headers: &HeaderMap) -> Self {
+        let text = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+
+        Self {
+            status,
+            request_id: text(Self::REQUEST_ID),
+            processing_time: text(Self::PROCESSING_TIME_MS)
+                .and_then(|v| v.parse::<f64>().ok())
+                .filter(|ms| *ms >= 0.0 && ms.is_finite())
+                .map(|ms| Duration::from_secs_f64(ms / 1000.0)),
+            model_revision: text(Self::MODEL_REVISION),
+            rate_limit: RateLimitInfo::from_headers(headers),
+        }
+    }
+}
+
+/// Typed response body together with its response metadata
+#[derive(Debug, Clone)]
+pub struct WithMeta<T> {
+    pub body: T,
+    pub meta: ResponseMeta,
+}
+
+impl<T> WithMeta<T> {
+    /// Discard the metadata and return the body
+    pub fn into_body(self) -> T {
+        self.body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ResponseMeta::REQUEST_ID, HeaderValue::from_static("req-123"));
+        headers.insert(ResponseMeta::PROCESSING_TIME_MS, HeaderValue::from_static("12.5"));
+        headers.insert(ResponseMeta::MODEL_REVISION, HeaderValue::from_static("main"));
+
+        let meta = ResponseMeta::from_headers(200, &headers);
+        assert_eq!(meta.status, 200);
+        assert_eq!(meta.request_id.as_deref(), Some("req-123"));
+        assert_eq!(meta.processing_time, Some(Duration::from_micros(12_500)));
+        assert_eq!(meta.model_revision.as_deref(), Some("main"));
+        assert!(meta.rate_limit.is_none());
+
+        let meta = ResponseMeta::from_headers(204, &HeaderMap::new());
+        assert!(meta.request_id.is_none() && meta.processing_time.is_none());
+    }
+}
@@ -4,6 +4,7 @@
  * Model types used by the HTTP client for requests and responses.
  */
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -11,6 +12,167 @@ use std::path::PathBuf;
 // Re-export server types for convenience
 pub use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse, ModelInfo};
 
+/// Model weight format
+///
+/// Serialized as a lowercase string; unknown formats are kept, lowercased, in `Other`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum ModelFormat {
+    Gguf,
+    Safetensors,
+    Pytorch,
+    Onnx,
+    Other(String),
+}
+
+impl ModelFormat {
+    /// Get the canonical string form
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Gguf => "gguf",
+            Self::Safetensors => "safetensors",
+            Self::Pytorch => "pytorch",
+            Self::Onnx => "onnx",
+            Self::Other(other) => other,
+        }
+    }
+
+    /// Detect the format of a weight file from its extension
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        let (_, extension) = filename.rsplit_once('.')?;
+        match extension.to_ascii_lowercase().as_str() {
+            "gguf" => Some(Self::Gguf),
+            "safetensors" => Some(Self::Safetensors),
+            "bin" | "pt" | "pth" => Some(Self::Pytorch),
+            "onnx" => Some(Self::Onnx),
+            _ => None,
+        }
+    }
+}
+
+impl From<String> for ModelFormat {
+    fn from(value: String) -> Self {
+        let value = value.to_ascii_lowercase();
+        match value.as_str() {
+            "gguf" => Self::Gguf,
+            "safetensors" => Self::Safetensors,
+            "pytorch" => Self::Pytorch,
+            "onnx" => Self::Onnx,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl From<&str> for ModelFormat {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<ModelFormat> for String {
+    fn from(value: ModelFormat) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl std::str::FromStr for ModelFormat {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl std::fmt::Display for ModelFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Hub pipeline tag describing what a model does
+///
+/// Serialized as the hub's kebab-case tag; unknown tags are kept, lowercased, in `Other`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum PipelineTag {
+    TextGeneration,
+    Text2TextGeneration,
+    FeatureExtraction,
+    SentenceSimilarity,
+    ImageTextToText,
+    AutomaticSpeechRecognition,
+    TextToSpeech,
+    Other(String),
+}
+
+impl PipelineTag {
+    /// Get the hub tag string
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::TextGeneration => "text-generation",
+            Self::Text2TextGeneration => "text2text-generation",
+            Self::FeatureExtraction => "feature-extraction",
+            Self::SentenceSimilarity => "sentence-similarity",
+            Self::ImageTextToText => "image-text-to-text",
+            Self::AutomaticSpeechRecognition => "automatic-speech-recognition",
+            Self::TextToSpeech => "text-to-speech",
+            Self::Other(other) => other,
+        }
+    }
+
+    /// Whether models with this tag can serve chat completions
+    pub fn is_text_generation(&self) -> bool {
+        matches!(self, Self::TextGeneration | Self::Text2TextGeneration | Self::ImageTextToText)
+    }
+
+    /// Read the `pipeline_tag` of a raw model list entry, if it has one
+    pub fn of_model_entry(entry: &serde_json::Value) -> Option<Self> {
+        entry.get("pipeline_tag")?.as_str().map(Self::from)
+    }
+}
+
+impl From<String> for PipelineTag {
+    fn from(value: String) -> Self {
+        let value = value.to_ascii_lowercase();
+        match value.as_str() {
+            "text-generation" => Self::TextGeneration,
+            "text2text-generation" => Self::Text2TextGeneration,
+            "feature-extraction" => Self::FeatureExtraction,
+            "sentence-similarity" => Self::SentenceSimilarity,
+            "image-text-to-text" => Self::ImageTextToText,
+            "automatic-speech-recognition" => Self::AutomaticSpeechRecognition,
+            "text-to-speech" => Self::TextToSpeech,
+            _ => Self::Other(value),
+        }
+    }
+}
+
+impl From<&str> for PipelineTag {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<PipelineTag> for String {
+    fn from(value: PipelineTag) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl std::str::FromStr for PipelineTag {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl std::fmt::Display for PipelineTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Response wrapper for model list operations
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelListResponse {
@@ -29,7 +191,7 @@ pub struct LocalModelInfo {
     /// Size of the model file in bytes
     pub size_bytes: u64,
     /// When the file was last modified
-    pub last_modified: chrono::DateTime<chrono::Utc>,
+    pub last_modified: DateTime<Utc>,
     /// Extracted metadata (if available)
     pub metadata: Option<serde_json::Value>,
     /// Whether this model file is currently loaded in memory
@@ -46,14 +208,41 @@ pub struct LocalModelsResponse {
 }
 
 /// Health check information
+///
+/// Fields missing from older servers fall back to defaults instead of failing.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct HealthInfo {
     pub status: String,
-    pub timestamp: String,
+    /// Report time, if the server includes one in RFC 3339 format
+    #[serde(default, deserialize_with = "lenient_timestamp")]
+    pub timestamp: Option<DateTime<Utc>>,
+    #[serde(default)]
     pub server_version: String,
+    #[serde(default)]
     pub uptime_seconds: u64,
 }
 
+impl HealthInfo {
+    /// When the server started, derived from the report timestamp and uptime
+    pub fn started_at(&self) -> Option<DateTime<Utc>> {
+        self.timestamp
+            .map(|timestamp| timestamp - chrono::Duration::seconds(self.uptime_seconds as i64))
+    }
+}
+
+/// Parse an RFC 3339 timestamp, treating any other value as missing
+fn lenient_timestamp<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<serde_json::Value>::deserialize(deserializer)?;
+    Ok(value
+        .as_ref()
+        .and_then(serde_json::Value::as_str)
+        .and_then(|text| DateTime::parse_from_rfc3339(text).ok())
+        .map(|timestamp| timestamp.with_timezone(&Utc)))
+}
+
 /// Load model request
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LoadModelRequest {
@@ -102,20 +291,41 @@ pub struct UnloadModelResponse {
 }
 
 /// Model status information
+///
+/// Only the instance and model IDs are required; older servers may omit the rest.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModelStatusInfo {
     pub instance_id: String,
     pub model_id: String,
+    #[serde(default = "unknown_status")]
     pub status: String,
+    #[serde(default)]
     pub memory_usage_bytes: u64,
-    pub loaded_at: String,
+    #[serde(default)]
+    pub loaded_at: Option<DateTime<Utc>>,
+}
+
+impl ModelStatusInfo {
+    /// How long this instance has been loaded, if the server reported a load time
+    pub fn uptime(&self) -> Option<chrono::Duration> {
+        self.uptime_at(Utc::now())
+    }
+
+    /// How long this instance had been loaded at the given time
+    pub fn uptime_at(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        self.loaded_at.map(|loaded_at| now.signed_duration_since(loaded_at))
+    }
+}
+
+fn unknown_status() -> String {
+    "unknown".to_string()
 }
 
 /// Download model request
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DownloadModelRequest {
     pub model_name: String,
-    pub format_hint: Option<String>,
+    pub format_hint: Option<ModelFormat>,
     pub force_redownload: bool,
     pub custom_directory: Option<String>,
 }
@@ -128,7 +338,7 @@ pub struct DownloadModelResponse {
     pub model_name: String,
     pub model_id: Option<String>,
     pub download_path: Option<String>,
-    pub detected_format: Option<String>,
+    pub detected_format: Option<ModelFormat>,
     pub size_bytes: Option<u64>,
     pub duration_ms: Option<u64>,
     pub error_details: Option<String>,
@@ -192,6 +402,37 @@ impl ChatRequestBuilder {
         self
     }
 
+    pub fn top_p(mut self, top_p: f32) -> Self {
+        self.request.top_p = Some(top_p);
+        self
+    }
+
+    pub fn presence_penalty(mut self, presence_penalty: f32) -> Self {
+        self.request.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    pub fn frequency_penalty(mut self, frequency_penalty: f32) -> Self {
+        self.request.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    /// Add a stop sequence; may be called repeatedly
+    pub fn stop<S: Into<String>>(mut self, stop: S) -> Self {
+        self.request.stop.get_or_insert_with(Vec::new).push(stop.into());
+        self
+    }
+
+    pub fn n(mut self, n: u32) -> Self {
+        self.request.n = Some(n);
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.request.seed = Some(seed);
+        self
+    }
+
     pub fn stream(mut self, stream: bool) -> Self {
         self.request.stream = Some(stream);
         self
@@ -206,4 +447,132 @@ impl Default for ChatRequestBuilder {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_timestamp_round_trip() {
+        let json = r#"{"status":"healthy","timestamp":"2024-05-01T12:00:00Z","server_version":"0.1.0","uptime_seconds":3600}"#;
+        let health: HealthInfo = serde_json::from_str(json).unwrap();
+
+        assert_eq!(health.timestamp.unwrap().to_rfc3339(), "2024-05-01T12:00:00+00:00");
+        assert_eq!(health.started_at().unwrap().to_rfc3339(), "2024-05-01T11:00:00+00:00");
+
+        let round_trip: HealthInfo = serde_json::from_str(&serde_json::to_string(&health).unwrap()).unwrap();
+        assert_eq!(round_trip.timestamp, health.timestamp);
+    }
+
+    #[test]
+    fn test_model_format_serde() {
+        let formats: Vec<ModelFormat> = serde_json::from_str(r#"["gguf","SafeTensors","MLX"]"#).unwrap();
+        assert_eq!(
+            formats,
+            vec![ModelFormat::Gguf, ModelFormat::Safetensors, ModelFormat::Other("mlx".to_string())]
+        );
+        assert_eq!(serde_json::to_string(&formats).unwrap(), r#"["gguf","safetensors","mlx"]"#);
+
+        assert_eq!(ModelFormat::from_filename("model.Q4_K_M.gguf"), Some(ModelFormat::Gguf));
+        assert_eq!(ModelFormat::from_filename("pytorch_model.bin"), Some(ModelFormat::Pytorch));
+        assert_eq!(ModelFormat::from_filename("README"), None);
+    }
+
+    #[test]
+    fn test_pipeline_tag_serde() {
+        let tag: PipelineTag = serde_json::from_str(r#""text-generation""#).unwrap();
+        assert_eq!(tag, PipelineTag::TextGeneration);
+        assert!(tag.is_text_generation());
+
+        let tag: PipelineTag = "image-segmentation".parse().unwrap();
+        assert_eq!(tag, PipelineTag::Other("image-segmentation".to_string()));
+        assert_eq!(serde_json::to_string(&tag).unwrap(), r#""image-segmentation""#);
+        assert!(!tag.is_text_generation());
+        assert_eq!(PipelineTag::from("Image-Segmentation"), tag);
+
+        let entry = serde_json::json!({"id": "m", "pipeline_tag": "Text-Generation"});
+        assert_eq!(PipelineTag::of_model_entry(&entry), Some(PipelineTag::TextGeneration));
+        assert_eq!(PipelineTag::of_model_entry(&serde_json::json!({"id": "m"})), None);
+    }
+
+    #[test]
+    fn test_model_status_uptime() {
+        let json = r#"{"instance_id":"inst-1","model_id":"test-model","status":"loaded","memory_usage_bytes":1024,"loaded_at":"2024-05-01T12:00:00.500+02:00"}"#;
+        let status: ModelStatusInfo = serde_json::from_str(json).unwrap();
+
+        let now: DateTime<Utc> = "2024-05-01T10:30:00.500Z".parse().unwrap();
+        assert_eq!(status.uptime_at(now), Some(chrono::Duration::minutes(30)));
+
+        let round_trip: ModelStatusInfo = serde_json::from_str(&serde_json::to_string(&status).unwrap()).unwrap();
+        assert_eq!(round_trip.loaded_at, status.loaded_at);
+    }
+
+    #[test]
+    fn test_chat_builder_sampling_controls() {
+        let request = ChatRequestBuilder::new()
+            .model("test-model")
+            .top_p(0.9)
+            .presence_penalty(0.5)
+            .frequency_penalty(0.25)
+            .stop("\n\n")
+            .stop("User:")
+            .n(2)
+            .seed(7)
+            .build();
+
+        assert_eq!(request.top_p, Some(0.9));
+        assert_eq!(request.presence_penalty, Some(0.5));
+        assert_eq!(request.frequency_penalty, Some(0.25));
+        assert_eq!(request.stop, Some(vec!["\n\n".to_string(), "User:".to_string()]));
+        assert_eq!(request.n, Some(2));
+        assert_eq!(request.seed, Some(7));
+    }
+
+    /// Health responses captured from successive server versions
+    const HEALTH_RESPONSES: &[&str] = &[
+        r#"{"status":"ok"}"#,
+        r#"{"status":"healthy","server_version":"0.1.0"}"#,
+        r#"{"status":"healthy","timestamp":"2024-05-01T12:00:00Z","server_version":"0.2.0","uptime_seconds":3600}"#,
+        r#"{"status":"healthy","timestamp":"2024-05-01T12:00:00Z","server_version":"0.3.0","uptime_seconds":3600,"backends":["llama.cpp"]}"#,
+    ];
+
+    /// Loaded-model responses captured from successive server versions
+    const LOADED_MODELS_RESPONSES: &[&str] = &[
+        r#"[{"instance_id":"inst-1","model_id":"test-model"}]"#,
+        r#"[{"instance_id":"inst-1","model_id":"test-model","status":"loaded","memory_usage_bytes":1024}]"#,
+        r#"[{"instance_id":"inst-1","model_id":"test-model","status":"loaded","memory_usage_bytes":1024,"loaded_at":"2024-05-01T12:00:00Z"}]"#,
+        r#"[{"instance_id":"inst-1","model_id":"test-model","status":"loaded","memory_usage_bytes":1024,"loaded_at":"2024-05-01T12:00:00Z","device":"cuda:0"}]"#,
+        r#"[]"#,
+    ];
+
+    #[test]
+    fn test_response_compatibility() {
+        for json in HEALTH_RESPONSES {
+            let health: HealthInfo = serde_json::from_str(json)
+                .unwrap_or_else(|e| panic!("failed to parse health response {}: {}", json, e));
+            assert!(!health.status.is_empty());
+        }
+
+        for json in LOADED_MODELS_RESPONSES {
+            let models: Vec<ModelStatusInfo> = serde_json::from_str(json)
+                .unwrap_or_else(|e| panic!("failed to parse loaded models response {}: {}", json, e));
+            assert!(models.iter().all(|model| model.model_id == "test-model"));
+        }
+
+        let models: Vec<ModelStatusInfo> = serde_json::from_str(LOADED_MODELS_RESPONSES[0]).unwrap();
+        assert_eq!(models[0].status, "unknown");
+        assert_eq!(models[0].memory_usage_bytes, 0);
+        assert!(models[0].uptime().is_none());
+
+        let health: HealthInfo = serde_json::from_str(HEALTH_RESPONSES[0]).unwrap();
+        assert!(health.timestamp.is_none());
+        assert!(health.started_at().is_none());
+
+        for timestamp in [r#""2024-05-01 12:00:00""#, "1714564800", "null"] {
+            let json = format!(r#"{{"status":"healthy","timestamp":{}}}"#, timestamp);
+            let health: HealthInfo = serde_json::from_str(&json).unwrap();
+            assert!(health.timestamp.is_none(), "{} should be ignored", timestamp);
+        }
+    }
+}
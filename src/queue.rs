@@ -0,0 +1,216 @@
+/*!
+ * Priority-Aware Request Queue
+ * 
+ * Client-side admission control for inference calls. When a concurrency
+ * limit is configured, waiting requests are admitted by priority so
+ * interactive calls overtake queued batch work.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+use crate::error::{ClientError, ClientResult};
+
+/// Priority of a queued request
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RequestPriority {
+    /// Background work that can wait
+    Batch,
+    /// User-facing requests that should be served first
+    #[default]
+    Interactive,
+}
+
+/// Request waiting for a free slot
+struct Waiter {
+    priority: RequestPriority,
+    sequence: u64,
+    sender: oneshot::Sender<PriorityPermit>,
+}
+
+impl PartialEq for Waiter {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Waiter {}
+
+impl PartialOrd for Waiter {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Waiter {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Higher priority first, then first-come first-served
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct LimiterState {
+    available: usize,
+    next_sequence: u64,
+    waiters: BinaryHeap<Waiter>,
+}
+
+/// Concurrency limiter that admits waiters in priority order
+pub(crate) struct PriorityLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl std::fmt::Debug for PriorityLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (available, queued) = self
+            .state
+            .lock()
+            .map(|state| (state.available, state.waiters.len()))
+            .unwrap_or((0, 0));
+        f.debug_struct("PriorityLimiter")
+            .field("available", &available)
+            .field("queued", &queued)
+            .finish()
+    }
+}
+
+impl PriorityLimiter {
+    /// Create a limiter with the given number of slots
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(LimiterState {
+                available: capacity,
+                next_sequence: 0,
+                waiters: BinaryHeap::new(),
+            }),
+        }
+    }
+
+    /// Wait for a slot, queued behind higher-priority requests
+    pub(crate) async fn acquire(self: &Arc<Self>, priority: RequestPriority) -> ClientResult<PriorityPermit> {
+        let receiver = {
+            let mut state = self.lock_state()?;
+
+            if state.available > 0 && state.waiters.is_empty() {
+                state.available -= 1;
+                return Ok(PriorityPermit { limiter: Some(self.clone()) });
+            }
+
+            let (sender, receiver) = oneshot::channel();
+            let sequence = state.next_sequence;
+            state.next_sequence += 1;
+            state.waiters.push(Waiter { priority, sequence, sender });
+            receiver
+        };
+
+        receiver
+            .await
+            .map_err(|_| ClientError::Cancelled("Request queue closed".to_string()))
+    }
+
+    /// Number of requests currently waiting for a slot
+    pub(crate) fn queued(&self) -> usize {
+        self.state.lock().map(|state| state.waiters.len()).unwrap_or(0)
+    }
+
+    fn lock_state(&self) -> ClientResult<std::sync::MutexGuard<'_, LimiterState>> {
+        self.state
+            .lock()
+            .map_err(|_| ClientError::Cancelled("Request queue lock poisoned".to_string()))
+    }
+
+    /// Hand a released slot to the next live waiter, or return it to the pool
+    fn release(self: &Arc<Self>) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        while let Some(waiter) = state.waiters.pop() {
+            let permit = PriorityPermit { limiter: Some(self.clone()) };
+            match waiter.sender.send(permit) {
+                Ok(()) => return,
+                Err(mut permit) => {
+                    // Waiter gave up; disarm the permit and try the next one
+                    permit.limiter = None;
+                }
+            }
+        }
+
+        state.available += 1;
+    }
+}
+
+/// Slot held by an admitted request, released on drop
+pub struct PriorityPermit {
+    limiter: Option<Arc<PriorityLimiter>>,
+}
+
+impl std::fmt::Debug for PriorityPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PriorityPermit").finish_non_exhaustive()
+    }
+}
+
+impl Drop for PriorityPermit {
+    fn drop(&mut self) {
+        if let Some(limiter) = self.limiter.take() {
+            limiter.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_interactive_preempts_batch() {
+        let limiter = Arc::new(PriorityLimiter::new(1));
+        let held = limiter.acquire(RequestPriority::Batch).await.unwrap();
+
+        let (order_tx, mut order_rx) = tokio::sync::mpsc::unbounded_channel();
+        let waiters = [("batch", RequestPriority::Batch), ("interactive", RequestPriority::Interactive)];
+        for (queued, (name, priority)) in waiters.into_iter().enumerate() {
+            let task_limiter = limiter.clone();
+            let order_tx = order_tx.clone();
+            tokio::spawn(async move {
+                let _permit = task_limiter.acquire(priority).await.unwrap();
+                order_tx.send(name).unwrap();
+            });
+            // Make sure each waiter is queued before the next one arrives
+            while limiter.queued() <= queued {
+                tokio::task::yield_now().await;
+            }
+        }
+
+        drop(held);
+        assert_eq!(order_rx.recv().await, Some("interactive"));
+        assert_eq!(order_rx.recv().await, Some("batch"));
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_waiter_returns_slot() {
+        let limiter = Arc::new(PriorityLimiter::new(1));
+        let held = limiter.acquire(RequestPriority::Interactive).await.unwrap();
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire(RequestPriority::Batch).await.map(|_| ()) }
+        });
+        while limiter.queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+        waiting.abort();
+        let _ = waiting.await;
+
+        drop(held);
+        let permit = limiter.acquire(RequestPriority::Batch).await;
+        assert!(permit.is_ok());
+    }
+}
@@ -0,0 +1,175 @@
+/*!
+ * Rate Limit Information
+ * 
+ * Parsing of rate-limit response headers returned by the server.
+ */
+
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Rate-limit state reported by the server on the most recent response
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RateLimitInfo {
+    /// Maximum requests allowed in the current window
+    pub limit_requests: Option<u64>,
+    /// Requests remaining in the current window
+    pub remaining_requests: Option<u64>,
+    /// Maximum tokens allowed in the current window
+    pub limit_tokens: Option<u64>,
+    /// Tokens remaining in the current window
+    pub remaining_tokens: Option<u64>,
+    /// Time until the request budget resets
+    pub reset_requests: Option<Duration>,
+    /// Time until the token budget resets
+    pub reset_tokens: Option<Duration>,
+    /// Server-requested delay before retrying (from `Retry-After`)
+    pub retry_after: Option<Duration>,
+    /// When these headers were received
+    pub observed_at: DateTime<Utc>,
+}
+
+impl RateLimitInfo {
+    pub const LIMIT_REQUESTS: &'static str = "x-ratelimit-limit-requests";
+    pub const REMAINING_REQUESTS: &'static str = "x-ratelimit-remaining-requests";
+    pub const LIMIT_TOKENS: &'static str = "x-ratelimit-limit-tokens";
+    pub const REMAINING_TOKENS: &'static str = "x-ratelimit-remaining-tokens";
+    pub const RESET_REQUESTS: &'static str = "x-ratelimit-reset-requests";
+    pub const RESET_TOKENS: &'static str = "x-ratelimit-reset-tokens";
+    pub const RETRY_AFTER: &'static str = "retry-after";
+
+    /// Parse rate-limit headers, returning `None` if none are present
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let text = |name: &str| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+        let number = |name: &str| text(name).and_then(|v| v.parse::<u64>().ok());
+        let duration = |name: &str| text(name).and_then(parse_reset_duration);
+
+        let info = Self {
+            limit_requests: number(Self::LIMIT_REQUESTS),
+            remaining_requests: number(Self::REMAINING_REQUESTS),
+            limit_tokens: number(Self::LIMIT_TOKENS),
+            remaining_tokens: number(Self::REMAINING_TOKENS),
+            reset_requests: duration(Self::RESET_REQUESTS),
+            reset_tokens: duration(Self::RESET_TOKENS),
+            retry_after: duration(Self::RETRY_AFTER),
+            observed_at: Utc::now(),
+        };
+
+        let has_any = info.limit_requests.is_some()
+            || info.remaining_requests.is_some()
+            || info.limit_tokens.is_some()
+            || info.remaining_tokens.is_some()
+            || info.reset_requests.is_some()
+            || info.reset_tokens.is_some()
+            || info.retry_after.is_some();
+
+        has_any.then_some(info)
+    }
+
+    /// How long to wait before the next request, if the reported budget is exhausted
+    pub fn wait_time(&self) -> Option<Duration> {
+        self.wait_time_at(Utc::now())
+    }
+
+    /// How long to wait at the given time, if the reported budget is exhausted
+    pub fn wait_time_at(&self, now: DateTime<Utc>) -> Option<Duration> {
+        let exhausted = |remaining: Option<u64>, reset: Option<Duration>| match (remaining, reset) {
+            (Some(0), Some(reset)) => Some(reset),
+            _ => None,
+        };
+
+        let reset = [
+            self.retry_after,
+            exhausted(self.remaining_requests, self.reset_requests),
+            exhausted(self.remaining_tokens, self.reset_tokens),
+        ]
+        .into_iter()
+        .flatten()
+        .max()?;
+
+        let elapsed = now.signed_duration_since(self.observed_at).to_std().unwrap_or(Duration::ZERO);
+        reset.checked_sub(elapsed).filter(|wait| !wait.is_zero())
+    }
+}
+
+/// Parse a reset duration given either as plain seconds ("30", "1.5")
+/// or with unit suffixes ("20ms", "6m0s", "1h2m3s")
+fn parse_reset_duration(value: &str) -> Option<Duration> {
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs >= 0.0 && secs.is_finite()).then(|| Duration::from_secs_f64(secs));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+
+    while !rest.is_empty() {
+        let number_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let amount: f64 = rest[..number_end].parse().ok()?;
+        rest = &rest[number_end..];
+
+        let unit_end = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_end] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+        rest = &rest[unit_end..];
+
+        total += Duration::from_secs_f64(amount * seconds_per_unit);
+    }
+
+    Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_parse_reset_duration() {
+        assert_eq!(parse_reset_duration("30"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_reset_duration("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_reset_duration("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_reset_duration("1h2m3s"), Some(Duration::from_secs(3723)));
+        assert_eq!(parse_reset_duration("soon"), None);
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(RateLimitInfo::from_headers(&headers).is_none());
+
+        headers.insert(RateLimitInfo::REMAINING_REQUESTS, HeaderValue::from_static("42"));
+        headers.insert(RateLimitInfo::RESET_TOKENS, HeaderValue::from_static("1.5s"));
+        headers.insert(RateLimitInfo::RETRY_AFTER, HeaderValue::from_static("2"));
+
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+        assert_eq!(info.remaining_requests, Some(42));
+        assert_eq!(info.limit_requests, None);
+        assert_eq!(info.reset_tokens, Some(Duration::from_millis(1500)));
+        assert_eq!(info.retry_after, Some(Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_wait_time() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RateLimitInfo::REMAINING_REQUESTS, HeaderValue::from_static("0"));
+        headers.insert(RateLimitInfo::RESET_REQUESTS, HeaderValue::from_static("10s"));
+        let info = RateLimitInfo::from_headers(&headers).unwrap();
+
+        let later = info.observed_at + chrono::Duration::seconds(4);
+        assert_eq!(info.wait_time_at(later), Some(Duration::from_secs(6)));
+        assert_eq!(info.wait_time_at(info.observed_at + chrono::Duration::seconds(11)), None);
+
+        headers.insert(RateLimitInfo::REMAINING_REQUESTS, HeaderValue::from_static("5"));
+        assert_eq!(RateLimitInfo::from_headers(&headers).unwrap().wait_time(), None);
+    }
+}
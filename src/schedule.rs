@@ -0,0 +1,96 @@
+/*!
+ * Scheduled Requests
+ * 
+ * Deferred chat completions that run at a given instant on a background
+ * task, with handles to cancel them or await their result.
+ */
+
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::client::LmoClient;
+use crate::error::{ClientError, ClientResult};
+use crate::queue::RequestPriority;
+use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse};
+
+/// Handle to a chat completion scheduled for later execution
+///
+/// Dropping the handle does not cancel the request; call [`cancel`](Self::cancel).
+#[derive(Debug)]
+pub struct ScheduledRequest {
+    handle: JoinHandle<ClientResult<ChatCompletionResponse>>,
+    scheduled_at: Instant,
+}
+
+impl ScheduledRequest {
+    /// Get the instant the request is scheduled to run at
+    pub fn scheduled_at(&self) -> Instant {
+        self.scheduled_at
+    }
+
+    /// Cancel the request, aborting it if it is already running
+    pub fn cancel(&self) {
+        self.handle.abort();
+    }
+
+    /// Check whether the request has completed or been cancelled
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// Wait for the request to run and return its result
+    pub async fn result(self) -> ClientResult<ChatCompletionResponse> {
+        match self.handle.await {
+            Ok(result) => result,
+            Err(e) if e.is_cancelled() => {
+                Err(ClientError::Cancelled("Scheduled request was cancelled".to_string()))
+            }
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+}
+
+impl LmoClient {
+    /// Schedule a chat completion to run at the given instant
+    ///
+    /// Scheduled requests are queued with batch priority when `max_in_flight` is set.
+    pub fn schedule(&self, request: ChatCompletionRequest, at: Instant) -> ScheduledRequest {
+        debug!("Scheduling chat completion for model: {}", request.model);
+
+        let client = self.clone();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep_until(at).await;
+            client.chat_completion_with_priority(request, RequestPriority::Batch).await
+        });
+
+        ScheduledRequest {
+            handle,
+            scheduled_at: at,
+        }
+    }
+
+    /// Schedule a chat completion to run after the given delay
+    pub fn schedule_after(&self, request: ChatCompletionRequest, delay: Duration) -> ScheduledRequest {
+        self.schedule(request, Instant::now() + delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cancel_scheduled_request() {
+        let client = LmoClient::new().unwrap();
+        let request = client.chat().model("test-model").message("user", "Hello").build();
+
+        let scheduled = client.schedule_after(request, Duration::from_secs(3600));
+        assert!(!scheduled.is_finished());
+
+        scheduled.cancel();
+        let result = scheduled.result().await;
+        assert!(matches!(result, Err(ClientError::Cancelled(_))));
+    }
+}
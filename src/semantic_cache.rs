@@ -0,0 +1,281 @@
+/*!
+ * Semantic Cache
+ *
+ * In-memory cache of chat completions keyed by an embedding of the prompt,
+ * so a sufficiently similar recent prompt is answered without inference.
+ */
+
+use futures::future::BoxFuture;
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::client::LmoClient;
+use crate::error::ClientResult;
+use lmoserver::shared_types::{ChatCompletionRequest, ChatCompletionResponse};
+
+/// Turns prompt text into an embedding vector
+pub trait Embedder: Send + Sync {
+    fn embed(&self, text: String) -> BoxFuture<'static, ClientResult<Vec<f32>>>;
+}
+
+impl<F, Fut> Embedder for F
+where
+    F: Fn(String) -> Fut + Send + Sync,
+    Fut: Future<Output = ClientResult<Vec<f32>>> + Send + 'static,
+{
+    fn embed(&self, text: String) -> BoxFuture<'static, ClientResult<Vec<f32>>> {
+        Box::pin(self(text))
+    }
+}
+
+/// Cached completion for one prompt
+#[derive(Debug)]
+struct CacheEntry {
+    /// Everything in the request except the messages, which must match exactly
+    params: String,
+    embedding: Vec<f32>,
+    response: ChatCompletionResponse,
+    stored_at: Instant,
+}
+
+/// Embedding-keyed cache of chat completions
+pub struct SemanticCache {
+    embedder: Arc<dyn Embedder>,
+    threshold: f32,
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<Vec<CacheEntry>>,
+}
+
+impl SemanticCache {
+    pub const DEFAULT_THRESHOLD: f32 = 0.95;
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(600);
+    pub const DEFAULT_MAX_ENTRIES: usize = 1000;
+
+    /// Create a cache that embeds prompts with the given embedder
+    pub fn new<E: Embedder + 'static>(embedder: E) -> Self {
+        Self {
+            embedder: Arc::new(embedder),
+            threshold: Self::DEFAULT_THRESHOLD,
+            ttl: Self::DEFAULT_TTL,
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Set the minimum cosine similarity for a prompt to count as a hit
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Set how long cached responses stay valid
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Set the maximum number of cached responses; the oldest are evicted first
+    ///
+    /// A limit of 0 disables caching.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Number of live entries
+    pub fn len(&self) -> usize {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict_expired(&mut entries);
+        entries.len()
+    }
+
+    /// Whether the cache holds no live entries
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all entries
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear();
+    }
+
+    /// Find the most similar cached response with the same parameters, if above the threshold
+    fn lookup(&self, params: &str, embedding: &[f32]) -> Option<(ChatCompletionResponse, f32)> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict_expired(&mut entries);
+
+        entries
+            .iter()
+            .filter(|entry| entry.params == params)
+            .map(|entry| (entry, dot(&entry.embedding, embedding)))
+            .filter(|(_, similarity)| *similarity >= self.threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entry, similarity)| (entry.response.clone(), similarity))
+    }
+
+    fn insert(&self, params: String, embedding: Vec<f32>, response: ChatCompletionResponse) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        self.evict_expired(&mut entries);
+
+        if entries.len() >= self.max_entries {
+            let excess = entries.len() + 1 - self.max_entries;
+            entries.drain(..excess);
+        }
+        entries.push(CacheEntry {
+            params,
+            embedding,
+            response,
+            stored_at: Instant::now(),
+        });
+    }
+
+    fn evict_expired(&self, entries: &mut Vec<CacheEntry>) {
+        entries.retain(|entry| entry.stored_at.elapsed() < self.ttl);
+    }
+}
+
+impl fmt::Debug for SemanticCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemanticCache")
+            .field("threshold", &self.threshold)
+            .field("ttl", &self.ttl)
+            .field("max_entries", &self.max_entries)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Chat completion that may have been served from the semantic cache
+#[derive(Debug, Clone)]
+pub struct CachedCompletion {
+    pub response: ChatCompletionResponse,
+    /// Whether the response came from the cache
+    pub cached: bool,
+    /// Similarity of the matched cached prompt, for cache hits
+    pub similarity: Option<f32>,
+}
+
+impl LmoClient {
+    /// Create a chat completion, answering from the semantic cache when a similar prompt was seen
+    ///
+    /// Only the messages are compared by similarity; the model and sampling
+    /// parameters (max_tokens, n, temperature, stop, seed, ...) must match
+    /// exactly. Without a configured cache this is a plain `chat_completion`.
+    pub async fn chat_completion_cached(&self, request: ChatCompletionRequest) -> ClientResult<CachedCompletion> {
+        let cache = match self.semantic_cache() {
+            Some(cache) => cache,
+            None => {
+                return Ok(CachedCompletion {
+                    response: self.chat_completion(request).await?,
+                    cached: false,
+                    similarity: None,
+                })
+            }
+        };
+
+        let params = request_params(&request)?;
+        let mut embedding = cache.embedder.embed(prompt_text(&request)).await?;
+        l2_normalize(&mut embedding);
+
+        if let Some((response, similarity)) = cache.lookup(&params, &embedding) {
+            debug!("Semantic cache hit for {} (similarity {:.3})", request.model, similarity);
+            return Ok(CachedCompletion {
+                response,
+                cached: true,
+                similarity: Some(similarity),
+            });
+        }
+
+        let response = self.chat_completion(request).await?;
+        cache.insert(params, embedding, response.clone());
+
+        Ok(CachedCompletion {
+            response,
+            cached: false,
+            similarity: None,
+        })
+    }
+}
+
+/// Serialize everything in the request except the messages
+fn request_params(request: &ChatCompletionRequest) -> ClientResult<String> {
+    let mut params = serde_json::to_value(request)?;
+    if let Some(params) = params.as_object_mut() {
+        params.remove("messages");
+    }
+    Ok(params.to_string())
+}
+
+/// Flatten the conversation into the text that is embedded
+fn prompt_text(request: &ChatCompletionRequest) -> String {
+    request
+        .messages
+        .iter()
+        .map(|message| format!("{}: {}", message.role, message.content))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Scale a vector to unit length so a dot product gives cosine similarity
+fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ChatRequestBuilder;
+
+    fn response(id: &str) -> ChatCompletionResponse {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "object": "chat.completion", "created": 0, "model": "m", "choices": []
+        }))
+        .unwrap()
+    }
+
+    async fn no_embedding(_text: String) -> ClientResult<Vec<f32>> {
+        Ok(Vec::new())
+    }
+
+    #[test]
+    fn test_lookup_threshold_and_params() {
+        let cache = SemanticCache::new(no_embedding).with_threshold(0.9).with_max_entries(2);
+        let request = ChatRequestBuilder::new().model("m").message("user", "hi").build();
+        let params = request_params(&request).unwrap();
+        cache.insert(params.clone(), vec![1.0, 0.0], response("a"));
+
+        let (hit, similarity) = cache.lookup(&params, &[0.96, 0.28]).unwrap();
+        assert_eq!(hit.id, "a");
+        assert!(similarity > 0.95);
+        assert!(cache.lookup(&params, &[0.0, 1.0]).is_none());
+
+        let mut other = request.clone();
+        other.messages[0].content = "a different prompt".to_string();
+        assert_eq!(request_params(&other).unwrap(), params);
+        let limited = ChatRequestBuilder::new().model("m").message("user", "hi").max_tokens(10).build();
+        assert!(cache.lookup(&request_params(&limited).unwrap(), &[1.0, 0.0]).is_none());
+
+        cache.insert(params.clone(), vec![0.0, 1.0], response("b"));
+        cache.insert(params.clone(), vec![0.6, 0.8], response("c"));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.lookup(&params, &[1.0, 0.0]).is_none());
+
+        let disabled = SemanticCache::new(no_embedding).with_max_entries(0);
+        disabled.insert(params, vec![1.0, 0.0], response("a"));
+        assert!(disabled.is_empty());
+    }
+}
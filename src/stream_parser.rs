@@ -0,0 +1,215 @@
+/*!
+ * Streaming Response Parser
+ *
+ * Incremental parser splitting streamed response bodies into JSON payloads,
+ * for both Server-Sent Events and newline-delimited JSON transports.
+ * Heartbeat and keep-alive messages are recognized and reported separately
+ * so callers can treat them as activity without trying to parse them.
+ */
+
+use tracing::debug;
+
+/// Wire format of a streamed response body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Server-Sent Events (`text/event-stream`)
+    Sse,
+    /// Newline-delimited JSON (`application/x-ndjson`)
+    JsonLines,
+}
+
+impl StreamFormat {
+    /// Detect the format from a `Content-Type` header, defaulting to SSE
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        let mime = content_type
+            .and_then(|value| value.split(';').next())
+            .map(|mime| mime.trim().to_ascii_lowercase());
+
+        match mime.as_deref() {
+            Some("application/x-ndjson")
+            | Some("application/ndjson")
+            | Some("application/jsonl")
+            | Some("application/json-lines")
+            | Some("application/jsonlines") => Self::JsonLines,
+            _ => Self::Sse,
+        }
+    }
+}
+
+/// A complete unit parsed from the stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StreamFrame {
+    /// JSON payload of one message
+    Data(String),
+    /// Heartbeat, keep-alive or comment-only message
+    KeepAlive,
+    /// End-of-stream marker (`[DONE]`)
+    Done,
+}
+
+/// Incremental parser over raw response bytes
+#[derive(Debug)]
+pub(crate) struct StreamParser {
+    format: StreamFormat,
+    buffer: Vec<u8>,
+    /// Data lines of the SSE event being assembled
+    data_lines: Vec<String>,
+    /// Event type of the SSE event being assembled
+    event_type: Option<String>,
+    /// Whether the SSE event being assembled contained a comment line
+    has_comment: bool,
+}
+
+impl StreamParser {
+    pub fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+            data_lines: Vec::new(),
+            event_type: None,
+            has_comment: false,
+        }
+    }
+
+    /// Feed a chunk of bytes, returning every frame it completes
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<StreamFrame> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut frames = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            self.parse_line(line.trim_end_matches(['\n', '\r']), &mut frames);
+        }
+
+        frames
+    }
+
+    /// Flush any frame left incomplete when the body ends
+    pub fn finish(&mut self) -> Vec<StreamFrame> {
+        let mut frames = Vec::new();
+
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&self.buffer).into_owned();
+            self.buffer.clear();
+            self.parse_line(line.trim_end_matches('\r'), &mut frames);
+        }
+        if self.format == StreamFormat::Sse {
+            self.dispatch_event(&mut frames);
+        }
+
+        frames
+    }
+
+    fn parse_line(&mut self, line: &str, frames: &mut Vec<StreamFrame>) {
+        match self.format {
+            StreamFormat::JsonLines => {
+                // Blank lines are the usual NDJSON keep-alive
+                if line.trim().is_empty() {
+                    frames.push(StreamFrame::KeepAlive);
+                } else {
+                    frames.push(Self::frame(line.trim()));
+                }
+            }
+            StreamFormat::Sse => {
+                if line.is_empty() {
+                    self.dispatch_event(frames);
+                } else if let Some(data) = line.strip_prefix("data:") {
+                    self.data_lines.push(Self::field_value(data).to_string());
+                } else if let Some(event) = line.strip_prefix("event:") {
+                    self.event_type = Some(Self::field_value(event).to_string());
+                } else if line.starts_with(':') {
+                    // SSE comment line (used for keep-alive)
+                    self.has_comment = true;
+                } else {
+                    // Other fields (id, retry) carry no payload
+                    debug!("Ignoring SSE line: {:?}", line);
+                }
+            }
+        }
+    }
+
+    /// Emit the SSE event assembled so far
+    fn dispatch_event(&mut self, frames: &mut Vec<StreamFrame>) {
+        let event_type = self.event_type.take();
+        let has_comment = std::mem::take(&mut self.has_comment);
+        let data = self.data_lines.join("\n");
+        let has_data = !self.data_lines.is_empty();
+        self.data_lines.clear();
+
+        let data = data.trim();
+        let is_heartbeat = matches!(event_type.as_deref(), Some("heartbeat") | Some("ping") | Some("keep-alive"))
+            || matches!(data, "ping" | "keep-alive" | "heartbeat");
+
+        if is_heartbeat || (has_comment && data.is_empty()) {
+            debug!("Received keep-alive event");
+            frames.push(StreamFrame::KeepAlive);
+        } else if has_data && !data.is_empty() {
+            frames.push(Self::frame(data));
+        }
+    }
+
+    /// Strip the single optional space following an SSE field name
+    fn field_value(value: &str) -> &str {
+        value.strip_prefix(' ').unwrap_or(value)
+    }
+
+    fn frame(payload: &str) -> StreamFrame {
+        if payload == "[DONE]" {
+            StreamFrame::Done
+        } else {
+            StreamFrame::Data(payload.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_detection() {
+        assert_eq!(StreamFormat::from_content_type(Some("text/event-stream")), StreamFormat::Sse);
+        assert_eq!(
+            StreamFormat::from_content_type(Some("application/x-ndjson; charset=utf-8")),
+            StreamFormat::JsonLines
+        );
+        assert_eq!(StreamFormat::from_content_type(None), StreamFormat::Sse);
+    }
+
+    #[test]
+    fn test_split_frames() {
+        let mut parser = StreamParser::new(StreamFormat::Sse);
+        assert!(parser.push(b"data: {\"a\":").is_empty());
+        assert_eq!(
+            parser.push(b"1}\n\nevent: message\ndata: {\"b\":2}\r\n\r\ndata: [DONE]\n\n"),
+            vec![
+                StreamFrame::Data("{\"a\":1}".to_string()),
+                StreamFrame::Data("{\"b\":2}".to_string()),
+                StreamFrame::Done,
+            ]
+        );
+
+        let mut parser = StreamParser::new(StreamFormat::JsonLines);
+        assert_eq!(
+            parser.push(b"{\"a\":1}\n\n{\"b\""),
+            vec![StreamFrame::Data("{\"a\":1}".to_string()), StreamFrame::KeepAlive]
+        );
+        assert_eq!(parser.push(b":2}"), vec![]);
+        assert_eq!(parser.finish(), vec![StreamFrame::Data("{\"b\":2}".to_string())]);
+    }
+
+    #[test]
+    fn test_keep_alive_frames() {
+        let mut parser = StreamParser::new(StreamFormat::Sse);
+        assert_eq!(
+            parser.push(b"event: heartbeat\ndata: ping\n\n: keep-alive\n\ndata: keep-alive\n\nevent: message\ndata: {\"a\":1}\n\n"),
+            vec![
+                StreamFrame::KeepAlive,
+                StreamFrame::KeepAlive,
+                StreamFrame::KeepAlive,
+                StreamFrame::Data("{\"a\":1}".to_string()),
+            ]
+        );
+    }
+}
@@ -5,13 +5,21 @@
  */
 
 use crate::error::{ClientError, ClientResult};
+use crate::guardrail::{Guardrails, StreamGuard};
+use crate::meta::ResponseMeta;
+use crate::queue::PriorityPermit;
+use crate::stream_parser::{StreamFrame, StreamParser};
 use futures::Stream;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::Response;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
 use std::task::{Context, Poll};
+use std::time::Duration;
 use tokio_stream::StreamExt;
 
+pub use crate::stream_parser::StreamFormat;
+
 /// Streaming chat completion response
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChatCompletionChunk {
@@ -36,39 +44,166 @@ pub struct ChunkDelta {
 }
 
 /// Stream wrapper for chat completion responses
+///
+/// Accepts both SSE and newline-delimited JSON bodies, chosen by the
+/// response content type.
 pub struct ChatCompletionStream {
     response: Response,
+    meta: ResponseMeta,
+    format: StreamFormat,
+    idle_timeout: Option<Duration>,
+    permit: Option<PriorityPermit>,
+    guardrails: Guardrails,
 }
 
 impl ChatCompletionStream {
     pub fn new(response: Response) -> Self {
-        Self { response }
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok());
+        
+        Self {
+            meta: ResponseMeta::from_response(&response),
+            format: StreamFormat::from_content_type(content_type),
+            response,
+            idle_timeout: None,
+            permit: None,
+            guardrails: Guardrails::default(),
+        }
+    }
+
+    /// Get the metadata from the stream's response headers
+    pub fn meta(&self) -> &ResponseMeta {
+        &self.meta
+    }
+
+    /// Get the wire format detected for this stream
+    pub fn format(&self) -> StreamFormat {
+        self.format
+    }
+
+    /// Fail the stream if nothing, not even a heartbeat, arrives within `timeout`
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Hold a concurrency permit for the lifetime of the stream
+    pub(crate) fn with_permit(mut self, permit: Option<PriorityPermit>) -> Self {
+        self.permit = permit;
+        self
+    }
+
+    /// Check the streamed content with the client's guardrails
+    pub(crate) fn with_guardrails(mut self, guardrails: Guardrails) -> Self {
+        self.guardrails = guardrails;
+        self
     }
 
     /// Convert into a stream of chat completion chunks
     pub async fn into_stream(self) -> ClientResult<impl Stream<Item = ClientResult<ChatCompletionChunk>>> {
-        let stream = self.response.bytes_stream();
-        Ok(stream.map(|result| {
-            match result {
-                Ok(bytes) => {
-                    // Parse SSE format: "data: {json}\n\n"
-                    let text = String::from_utf8_lossy(&bytes);
-                    
-                    // Simple parsing - in production this would be more robust
-                    if let Some(json_start) = text.find('{') {
-                        if let Some(json_end) = text.rfind('}') {
-                            let json_str = &text[json_start..=json_end];
-                            serde_json::from_str::<ChatCompletionChunk>(json_str)
-                                .map_err(|e| ClientError::JsonParseError(e))
-                        } else {
-                            Err(ClientError::InvalidResponse("No JSON end found".to_string()))
+        // Keep the in-flight permit alive until the stream is dropped
+        let permit = self.permit;
+        let idle_timeout = self.idle_timeout;
+        let mut guard = StreamGuard::new(self.guardrails);
+        let mut parser = StreamParser::new(self.format);
+        let mut bytes_stream = self.response.bytes_stream();
+        
+        Ok(async_stream::stream! {
+            let _permit = permit;
+            // Last chunk seen, used to carry text the guardrails held back until the end
+            let mut last_chunk: Option<ChatCompletionChunk> = None;
+            
+            'read: loop {
+                // Any received bytes, keep-alives included, restart the idle timer
+                let chunk = match idle_timeout {
+                    Some(timeout) => match tokio::time::timeout(timeout, bytes_stream.next()).await {
+                        Ok(chunk) => chunk,
+                        Err(_) => {
+                            yield Err(ClientError::TimeoutError(format!("No data received for {:?}", timeout)));
+                            return;
+                        }
+                    },
+                    None => bytes_stream.next().await,
+                };
+                
+                let (frames, ended) = match chunk {
+                    Some(Ok(bytes)) => (parser.push(&bytes), false),
+                    Some(Err(e)) => {
+                        yield Err(ClientError::HttpError(e));
+                        return;
+                    }
+                    None => (parser.finish(), true),
+                };
+                
+                for frame in frames {
+                    match frame {
+                        StreamFrame::Data(json) => {
+                            let chunk = Self::parse_chunk(&json, &mut guard);
+                            let blocked = matches!(chunk, Err(ClientError::GuardrailBlocked(_)));
+                            if let (Ok(chunk), false) = (&chunk, guard.is_empty()) {
+                                last_chunk = Some(chunk.clone());
+                            }
+                            yield chunk;
+                            if blocked {
+                                return;
+                            }
                         }
-                    } else {
-                        Err(ClientError::InvalidResponse("No JSON start found".to_string()))
+                        StreamFrame::KeepAlive => {}
+                        StreamFrame::Done => break 'read,
                     }
                 }
-                Err(e) => Err(ClientError::HttpError(e)),
+                
+                if ended {
+                    break;
+                }
+            }
+            
+            if let Some(chunk) = last_chunk {
+                let remaining = guard.finish_all();
+                if !remaining.is_empty() {
+                    yield Ok(Self::remainder_chunk(chunk, remaining));
+                }
+            }
+        })
+    }
+
+    /// Parse a chunk payload and run its content through the guardrails
+    fn parse_chunk(json: &str, guard: &mut StreamGuard) -> ClientResult<ChatCompletionChunk> {
+        let mut chunk = serde_json::from_str::<ChatCompletionChunk>(json)?;
+        
+        if !guard.is_empty() {
+            for choice in &mut chunk.choices {
+                let mut content = match choice.delta.content.take() {
+                    Some(delta) => guard.push(choice.index, &delta)?,
+                    None => String::new(),
+                };
+                if choice.finish_reason.is_some() {
+                    content.push_str(&guard.finish(choice.index));
+                }
+                if !content.is_empty() {
+                    choice.delta.content = Some(content);
+                }
             }
-        }))
+        }
+        
+        Ok(chunk)
     }
-}
\ No newline at end of file
+
+    /// Build a final chunk carrying text still held back when the stream ended
+    fn remainder_chunk(mut chunk: ChatCompletionChunk, remaining: Vec<(u32, String)>) -> ChatCompletionChunk {
+        chunk.choices = remaining
+            .into_iter()
+            .map(|(index, content)| ChunkChoice {
+                index,
+                delta: ChunkDelta {
+                    role: None,
+                    content: Some(content),
+                },
+                finish_reason: None,
+            })
+            .collect();
+        chunk
+    }
+}
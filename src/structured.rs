@@ -0,0 +1,148 @@
+/*!
+ * Structured JSON Output
+ *
+ * Chat completions parsed into typed JSON values, with opt-in repair of
+ * almost-valid model output: code fences and surrounding prose are trimmed,
+ * trailing commas removed, and optionally one retry asks the model to fix
+ * its own output.
+ */
+
+use serde::de::DeserializeOwned;
+use tracing::{debug, warn};
+
+use crate::client::LmoClient;
+use crate::error::{ClientError, ClientResult};
+use lmoserver::shared_types::{ChatCompletionRequest, ChatMessage};
+
+/// How hard to try when the model's output is not valid JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonRepair {
+    /// Parse the output as is
+    #[default]
+    Off,
+    /// Trim prose and code fences, and fix trailing commas
+    Syntax,
+    /// Syntax repair, then one retry with the parse error fed back to the model
+    SyntaxAndRetry,
+}
+
+impl LmoClient {
+    /// Create a chat completion and parse the first choice as JSON
+    pub async fn chat_completion_json<T: DeserializeOwned>(
+        &self,
+        request: ChatCompletionRequest,
+        repair: JsonRepair,
+    ) -> ClientResult<T> {
+        let output = self.first_choice_text(request.clone()).await?;
+
+        let error = match parse_json_output::<T>(&output, repair) {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+        if repair != JsonRepair::SyntaxAndRetry {
+            return Err(error);
+        }
+
+        warn!("Model output is not valid JSON, retrying with feedback: {}", error);
+        let mut retry = request;
+        retry.messages.push(ChatMessage {
+            role: "assistant".to_string(),
+            content: output,
+            name: None,
+        });
+        retry.messages.push(ChatMessage {
+            role: "user".to_string(),
+            content: format!(
+                "That reply was not valid JSON ({}). Reply again with only the corrected JSON and no other text.",
+                error
+            ),
+            name: None,
+        });
+
+        let output = self.first_choice_text(retry).await?;
+        parse_json_output(&output, repair)
+    }
+
+    async fn first_choice_text(&self, request: ChatCompletionRequest) -> ClientResult<String> {
+        let response = self.chat_completion(request).await?;
+        response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| ClientError::InvalidResponse("Chat completion returned no choices".to_string()))
+    }
+}
+
+/// Parse model output, repairing its syntax first if allowed
+fn parse_json_output<T: DeserializeOwned>(output: &str, repair: JsonRepair) -> ClientResult<T> {
+    match serde_json::from_str(output) {
+        Ok(value) => Ok(value),
+        Err(e) if repair == JsonRepair::Off => Err(ClientError::JsonParseError(e)),
+        Err(e) => {
+            let repaired = repair_json(output);
+            debug!("Repaired JSON output: {}", repaired);
+            serde_json::from_str(&repaired).map_err(|_| ClientError::JsonParseError(e))
+        }
+    }
+}
+
+/// Best-effort syntax repair of model-produced JSON
+///
+/// Keeps only the outermost object or array (dropping code fences and
+/// prose around it) and removes trailing commas.
+pub fn repair_json(text: &str) -> String {
+    let start = text.find(['{', '[']);
+    let end = text.rfind(['}', ']']);
+    let body = match (start, end) {
+        (Some(start), Some(end)) if start < end => &text[start..=end],
+        _ => text.trim(),
+    };
+
+    remove_trailing_commas(body)
+}
+
+/// Drop commas directly followed (ignoring whitespace) by `}` or `]`, outside strings
+fn remove_trailing_commas(json: &str) -> String {
+    let chars: Vec<char> = json.chars().collect();
+    let mut out = String::with_capacity(json.len());
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (i, &c) in chars.iter().enumerate() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[i + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test_repair_json() {
+        let output = "Sure! Here it is:\n```json\n{\"name\": \"a, }\", \"tags\": [1, 2,],}\n```\nLet me know.";
+        let value: Value = parse_json_output(output, JsonRepair::Syntax).unwrap();
+        assert_eq!(value, json!({"name": "a, }", "tags": [1, 2]}));
+
+        assert!(parse_json_output::<Value>(output, JsonRepair::Off).is_err());
+        assert!(parse_json_output::<Value>("no json at all", JsonRepair::Syntax).is_err());
+    }
+}
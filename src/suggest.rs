@@ -0,0 +1,139 @@
+/*!
+ * Model ID Suggestions
+ *
+ * Fuzzy matching of a mistyped model ID against known model IDs, used to
+ * attach "did you mean" suggestions to `ClientError::ModelNotFound`.
+ */
+
+use tracing::debug;
+
+use crate::client::LmoClient;
+use crate::error::{ClientError, ClientResult};
+
+/// Maximum number of suggestions returned
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Find the known model IDs closest to `target`, best match first
+///
+/// Matching is case-insensitive and also compares against the part after
+/// the organization prefix, so `llama-3-8b` finds `meta-llama/llama-3-8b`.
+pub fn suggest_model_ids<I, S>(target: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let target = target.trim().to_lowercase();
+    if target.is_empty() {
+        return Vec::new();
+    }
+
+    let max_distance = (target.chars().count() / 3).max(2);
+
+    let mut matches: Vec<(usize, String)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let candidate = candidate.as_ref();
+            let lower = candidate.to_lowercase();
+            let short = lower.rsplit('/').next().unwrap_or(&lower);
+
+            let distance = levenshtein(&target, &lower).min(levenshtein(&target, short));
+            let score = if distance == 0 {
+                // Same ID apart from case or organization prefix
+                0
+            } else if lower.contains(&target) {
+                distance.min(max_distance)
+            } else if distance <= max_distance {
+                distance
+            } else {
+                return None;
+            };
+
+            Some((score, candidate.to_string()))
+        })
+        .collect();
+
+    matches.sort();
+    matches.dedup_by(|a, b| a.1 == b.1);
+    matches.into_iter().take(MAX_SUGGESTIONS).map(|(_, id)| id).collect()
+}
+
+impl LmoClient {
+    /// Known model IDs close to `model_id`, best match first
+    ///
+    /// Uses the configured catalog cache if any, otherwise the server's model
+    /// list. Returns nothing if neither is available.
+    pub async fn suggest_models(&self, model_id: &str) -> Vec<String> {
+        let models = match self.catalog_cache() {
+            Some(cache) => self.list_models_cached(cache).await.map(|cached| cached.response),
+            None => self.list_models().await,
+        };
+
+        match models {
+            Ok(list) => suggest_model_ids(model_id, list.models.iter().map(|model| model.id.as_str())),
+            Err(e) => {
+                debug!("Could not fetch models for suggestions: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Attach model ID suggestions to a model-not-found error
+    pub(crate) async fn with_model_suggestions<T>(&self, model_id: &str, result: ClientResult<T>) -> ClientResult<T> {
+        match result {
+            Err(e) => Err(self.add_model_suggestions(model_id, e).await),
+            ok => ok,
+        }
+    }
+
+    /// Name the requested model in a model-not-found error and suggest close IDs
+    ///
+    /// The server's error text is kept, since a 404 can also mean a missing route.
+    pub(crate) async fn add_model_suggestions(&self, model_id: &str, error: ClientError) -> ClientError {
+        match error {
+            ClientError::ModelNotFound { message, .. } => ClientError::ModelNotFound {
+                model: model_id.to_string(),
+                message,
+                suggestions: self.suggest_models(model_id).await,
+            },
+            other => other,
+        }
+    }
+}
+
+/// Edit distance between two strings, counted in characters
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_model_ids() {
+        let known = [
+            "meta-llama/Llama-3-8B-Instruct",
+            "mistralai/Mistral-7B-Instruct-v0.2",
+            "Qwen/Qwen2-7B-Instruct",
+        ];
+
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(suggest_model_ids("llama-3-8b-instruct", known), vec![known[0]]);
+        assert_eq!(suggest_model_ids("mistral-7b-instrukt-v0.2", known), vec![known[1]]);
+        assert_eq!(suggest_model_ids("qwen2", known), vec![known[2]]);
+        assert!(suggest_model_ids("stable-diffusion", known).is_empty());
+    }
+}
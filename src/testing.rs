@@ -0,0 +1,38 @@
+/*!
+ * Integration Test Helpers
+ * 
+ * Assertions for tests that run against a live lmoserver. Enabled by the
+ * `testing` feature.
+ */
+
+use crate::client::LmoClient;
+use lmoserver::shared_types::ChatCompletionRequest;
+
+/// Assert that a seeded request produces identical choices on every run
+///
+/// Sends `request` `runs` times and panics if any run differs from the first.
+/// Panics as well if the request has no seed or a call fails.
+pub async fn assert_deterministic(client: &LmoClient, request: ChatCompletionRequest, runs: usize) {
+    assert!(request.seed.is_some(), "assert_deterministic requires a seeded request");
+    assert!(runs >= 2, "assert_deterministic needs at least two runs");
+
+    let mut expected = None;
+    for run in 0..runs {
+        let response = client
+            .chat_completion(request.clone())
+            .await
+            .unwrap_or_else(|e| panic!("Run {} failed: {}", run + 1, e));
+        let choices = serde_json::to_value(&response.choices)
+            .unwrap_or_else(|e| panic!("Run {} returned unserializable choices: {}", run + 1, e));
+
+        match &expected {
+            None => expected = Some(choices),
+            Some(first) => assert_eq!(
+                first, &choices,
+                "Run {} differs from run 1 for seed {:?}",
+                run + 1,
+                request.seed
+            ),
+        }
+    }
+}